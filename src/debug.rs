@@ -0,0 +1,126 @@
+//! Cheats for trying things out, only there when the game is started with `--debug`.
+
+use bevy::prelude::*;
+use rand::prelude::*;
+use tetris_core::{GameEvent, Piece};
+
+use crate::screens::AppState;
+use crate::{GameSet, Tetris};
+
+/// Whether the random filler is dropping blocks on the board.
+#[derive(Resource, Default)]
+struct RandomFiller {
+    enabled: bool,
+    timer: Timer,
+}
+
+#[derive(Component)]
+struct DebugIndicator;
+
+const PIECE_KEYS: [(KeyCode, Piece); 7] = [
+    (KeyCode::Key1, Piece::I),
+    (KeyCode::Key2, Piece::O),
+    (KeyCode::Key3, Piece::T),
+    (KeyCode::Key4, Piece::S),
+    (KeyCode::Key5, Piece::Z),
+    (KeyCode::Key6, Piece::J),
+    (KeyCode::Key7, Piece::L),
+];
+
+fn show_indicator(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/UbuntuMonoNerdFontCompleteMono.ttf");
+    commands.spawn((
+        TextBundle::from_sections([
+            TextSection::new(
+                "DEBUG\n",
+                TextStyle {
+                    font: font.clone(),
+                    font_size: 40.0,
+                    color: Color::RED,
+                },
+            ),
+            TextSection::new(
+                "Up teleport, 1-7 piece, B garbage, L level, F filler",
+                TextStyle {
+                    font,
+                    font_size: 20.0,
+                    color: Color::GRAY,
+                },
+            ),
+        ])
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                right: Val::Px(10.0),
+                bottom: Val::Px(10.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        }),
+        DebugIndicator,
+    ));
+}
+
+fn cheat_input(
+    keys: Res<Input<KeyCode>>,
+    mut tetris: ResMut<Tetris>,
+    mut filler: ResMut<RandomFiller>,
+    mut game_events: EventWriter<GameEvent>,
+) {
+    let game = &mut tetris.0;
+    if keys.any_just_pressed([KeyCode::Up, KeyCode::W]) {
+        game.debug_teleport();
+    }
+    for (key, piece) in PIECE_KEYS {
+        if keys.just_pressed(key) {
+            game_events.send_batch(game.debug_set_piece(piece));
+        }
+    }
+    if keys.just_pressed(KeyCode::B) {
+        let mut rng = rand::thread_rng();
+        let hole = rng.gen_range(0..game.board().width());
+        let piece = Piece::ALL[rng.gen_range(0..Piece::ALL.len())];
+        game.debug_add_garbage(hole, piece);
+    }
+    if keys.just_pressed(KeyCode::L) {
+        game_events.send_batch(game.debug_level_up());
+    }
+    if keys.just_pressed(KeyCode::F) {
+        filler.enabled = !filler.enabled;
+    }
+}
+
+/// Drops single blocks in random empty spots, handy for seeing how the board copes with mess.
+fn random_filler(mut tetris: ResMut<Tetris>, mut filler: ResMut<RandomFiller>, time: Res<Time>) {
+    if !filler.enabled || !filler.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let board = tetris.0.board_mut();
+    let mut rng = rand::thread_rng();
+    for _ in 0..20 {
+        let x = rng.gen_range(0..board.width());
+        let y = rng.gen_range(0..board.height());
+        if !board.is_filled(x, y) {
+            let piece = Piece::ALL[rng.gen_range(0..Piece::ALL.len())];
+            board.set(x, y, Some(piece));
+            break;
+        }
+    }
+}
+
+pub struct DebugPlugin;
+
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(RandomFiller {
+            enabled: false,
+            timer: Timer::from_seconds(0.125, TimerMode::Repeating),
+        })
+        .add_startup_system(show_indicator)
+        .add_systems(
+            (cheat_input, random_filler)
+                .in_set(OnUpdate(AppState::Playing))
+                .in_set(GameSet::Input),
+        );
+    }
+}
@@ -1,45 +1,132 @@
+mod debug;
+mod screens;
+mod sprint;
+#[cfg(test)]
+mod tests;
+mod ultra;
+
+use std::time::Duration;
+
 use bevy::{prelude::*, sprite::Anchor};
 use bevy_fps_counter::{FpsCounter, FpsCounterPlugin};
-use rand::prelude::*;
-use strum::EnumCount;
-use strum_macros::EnumCount as EnumCountMacro;
+use tetris_core::{
+    get_piece_meat_positions, Board, Delay, Game, GameEvent, Goal, GravityTable,
+    Input as GameInput, Piece, Ruleset, TICK,
+};
 
-const BOARD_SIZE: Vec2 = Vec2 { x: 15.0, y: 20.0 };
+use debug::DebugPlugin;
+use screens::{board_hidden, board_visible, AppState, CustomGravity, ScreensPlugin, StartSeed};
+use sprint::{PersonalBests, SprintPlugin, RECORDS_FILE};
+use ultra::UltraPlugin;
+
+/// Where the bottom left cell of the board goes, leaving room for the hold piece on the left.
 const BOARD_ORIGIN: Vec2 = Vec2 { x: 6.0, y: 1.0 };
-const WORLD_SIZE: Vec2 = Vec2 {
-    x: BOARD_SIZE.x + BOARD_ORIGIN.x + 2.,
-    y: BOARD_SIZE.y + 2.,
-};
+/// Room on the right of the board for the next queue.
+const QUEUE_WIDTH: f32 = 5.;
+
+/// How much of the buffer zone above the board gets drawn, so you can see what pokes out the top.
+const SHOWN_BUFFER_ROWS: usize = 2;
+
+/// How big the board being drawn is, taken from the game so modes can use different boards.
+#[derive(Resource, Default, PartialEq)]
+struct BoardLayout {
+    /// Width and visible height in cells, zero until the first board has been built.
+    size: Vec2,
+    /// Rows of the buffer zone drawn above the walls.
+    buffer_rows: usize,
+}
+
+impl BoardLayout {
+    fn for_board(board: &Board) -> Self {
+        Self {
+            size: Vec2::new(board.width() as f32, board.height() as f32),
+            buffer_rows: board.buffer_rows().min(SHOWN_BUFFER_ROWS),
+        }
+    }
+
+    /// Everything the camera has to fit: the hold piece, the walls, the board with its buffer rows
+    /// and the queue.
+    fn world_size(&self) -> Vec2 {
+        Vec2 {
+            x: self.size.x + BOARD_ORIGIN.x + QUEUE_WIDTH + 2.,
+            y: self.size.y + self.buffer_rows as f32 + 2.,
+        }
+    }
+
+    fn board_centre(&self) -> Vec2 {
+        BOARD_ORIGIN + self.size / 2.0
+    }
+}
+
+/// Anything drawn in the world that depends on the size of the board, rebuilt when it changes.
+#[derive(Component)]
+struct BoardView;
+
 fn setup_cam(mut commands: Commands) {
-    commands.spawn(Camera2dBundle {
-        transform: Transform {
-            translation: Vec3::new(WORLD_SIZE.x / 2.0, WORLD_SIZE.y / 2.0, 0.),
-            ..Default::default()
-        },
-        projection: OrthographicProjection {
-            scaling_mode: bevy::render::camera::ScalingMode::AutoMin {
-                min_width: WORLD_SIZE.x,
-                min_height: WORLD_SIZE.y,
-            },
-            ..Default::default()
-        },
-        ..Default::default()
-    });
+    commands.spawn(Camera2dBundle::default());
 }
 
-#[derive(Resource)]
-struct Score(usize);
+/// Points the camera at the middle of the world and zooms so all of it fits.
+fn fit_camera(
+    layout: &BoardLayout,
+    camera: &mut Transform,
+    projection: &mut OrthographicProjection,
+) {
+    let world_size = layout.world_size();
+    camera.translation = (world_size / 2.0).extend(camera.translation.z);
+    projection.scaling_mode = bevy::render::camera::ScalingMode::AutoMin {
+        min_width: world_size.x,
+        min_height: world_size.y,
+    };
+}
 
+/// The game itself, everything else in here just draws it and feeds it keys.
 #[derive(Resource)]
-struct Level(usize);
+struct Tetris(Game);
 
-#[derive(Resource)]
-struct Board {
+/// Starts a new game. Every start and restart goes through here, so nothing from the last game
+/// hangs around.
+#[derive(Default)]
+struct RestartGame {
+    /// Rules for a different mode, `None` plays the same one again.
+    rules: Option<Ruleset>,
+    /// `None` for a random one.
+    seed: Option<u64>,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn restart_game(
+    mut commands: Commands,
+    mut restarts: EventReader<RestartGame>,
+    mut tetris: ResMut<Tetris>,
+    mut latched: ResMut<LatchedInput>,
+    mut clock: ResMut<TickClock>,
+    mut bests: ResMut<PersonalBests>,
+    announcements: Query<Entity, With<Announcement>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let Some(restart) = restarts.iter().last() else {
+        return;
+    };
+    let seed = restart.seed.unwrap_or_else(rand::random);
+    match &restart.rules {
+        Some(rules) => tetris.0 = Game::seeded(rules.clone(), seed),
+        None => tetris.0.restart(seed),
+    }
+    latched.0 = GameInput::default();
+    clock.0 = Duration::ZERO;
+    bests.new_best = false;
+    for announcement in announcements.iter() {
+        commands.entity(announcement).despawn();
+    }
+    next_state.set(AppState::Countdown);
+}
+
+/// A grid of sprites we show and hide to draw blocks.
+struct TileGrid {
     width: usize,
     height: usize,
     squares: Vec<Vec<Entity>>,
-    board: Vec<Vec<bool>>,
-    colors: Vec<Vec<Color>>,
 }
 
 #[derive(Component)]
@@ -48,472 +135,299 @@ struct BoardTileColor(Color);
 #[derive(Component)]
 struct BoardTile;
 
-const MAX_LEVEL: usize = 29;
-
-impl Board {
+impl TileGrid {
     fn new(width: usize, height: usize) -> Self {
         Self {
             width,
             height,
             squares: vec![vec![Entity::from_raw(0); width]; height],
-            board: vec![vec![false; width]; height],
-            colors: vec![vec![Color::WHITE; width]; height],
         }
     }
 
-    fn update_sprite_colors(mut sprites: Query<(&mut Sprite, &mut BoardTileColor)>) {
-        for (mut sprite, color) in sprites.iter_mut() {
-            sprite.color = color.0;
+    fn hide(&self, commands: &mut Commands) {
+        for row in self.squares.iter() {
+            for entity in row.iter() {
+                commands.entity(*entity).insert(Visibility::Hidden);
+            }
         }
     }
 
-    fn update_board_sprites(
-        mut commands: Commands,
-        current_piece: Res<CurrentPiece>,
-        board: Res<Board>,
-        display_board: Res<DisplayBoard>,
-        current_piece_board: Res<CurrentPieceBoard>,
+    fn show_piece(
+        &self,
+        commands: &mut Commands,
+        piece: Piece,
+        rotation: i32,
+        color: Option<Color>,
     ) {
-        for row in 0..board.height {
-            for col in 0..board.width {
-                let entity = board.squares[row][col];
-                if board.board[row][col] {
+        let meat = get_piece_meat_positions(piece, IVec2::ZERO, rotation);
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let entity = self.squares[row][col];
+                if meat.contains(&IVec2::new(col as i32, row as i32)) {
                     commands.entity(entity).insert(Visibility::Visible);
-                    commands
-                        .entity(entity)
-                        .insert(BoardTileColor(board.colors[row][col]));
-                } else {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-            }
-        }
-
-        for row in 0..display_board.0.height {
-            for col in 0..display_board.0.width {
-                let entity = display_board.0.squares[row][col];
-                if display_board.0.board[row][col] {
-                    commands.entity(entity).insert(Visibility::Visible);
-                } else {
-                    commands.entity(entity).insert(Visibility::Hidden);
-                }
-            }
-        }
-
-        for row in 0..current_piece_board.0.height {
-            for col in 0..current_piece_board.0.width {
-                let entity = current_piece_board.0.squares[row][col];
-                if current_piece_board.0.board[row][col] {
-                    commands.entity(entity).insert(Visibility::Visible);
-                    commands
-                        .entity(entity)
-                        .insert(BoardTileColor(current_piece.get_color()));
+                    if let Some(color) = color {
+                        commands.entity(entity).insert(BoardTileColor(color));
+                    }
                 } else {
                     commands.entity(entity).insert(Visibility::Hidden);
                 }
             }
         }
     }
+}
 
-    #[allow(dead_code)]
-    fn debug(mut board: ResMut<Board>, mut timer: Local<Option<Timer>>, time: Res<Time>) {
-        if timer.is_none() {
-            *timer = Some(Timer::from_seconds(0.125, TimerMode::Repeating));
-        }
-        if timer.as_mut().unwrap().tick(time.delta()).just_finished() {
-            let mut tries = 20;
-            loop {
-                let x: usize = rand::thread_rng().gen_range(0..board.width);
-                let y: usize = rand::thread_rng().gen_range(0..board.height);
-                if board.board[y][x] == false {
-                    board.board[y][x] = true;
-                    break;
-                }
-                tries -= 1;
-                if tries == 0 {
-                    break;
-                }
-            }
-        }
+fn piece_color(piece: Piece) -> Color {
+    match piece {
+        Piece::I => Color::rgb(0.5, 1.0, 1.0),
+        Piece::O => Color::rgb(1.0, 1.0, 0.5),
+        Piece::T => Color::rgb(1.0, 0.5, 1.0),
+        Piece::S => Color::rgb(0.5, 1.0, 0.5),
+        Piece::Z => Color::rgb(1.0, 0.5, 0.5),
+        Piece::J => Color::rgb(0.5, 0.5, 1.0),
+        Piece::L => Color::rgb(1.0, 0.7, 0.5),
     }
+}
 
-    fn assess_board(
-        mut board: ResMut<Board>,
-        mut score: ResMut<Score>,
-        mut level: ResMut<Level>,
-        mut cleared_lines: Local<Option<u32>>,
-    ) {
-        if cleared_lines.is_none() {
-            *cleared_lines = Some(0);
-        }
-        let mut rows_to_remove = vec![];
-        for row in 0..board.height {
-            let full = board.board[row].iter().all(|&x| x);
-            if full {
-                rows_to_remove.push(row);
-            }
-        }
-
-        for row in rows_to_remove.iter().rev() {
-            board.board.remove(*row);
-            board.colors.remove(*row);
-            let width: usize = board.width;
-            board.board.push(vec![false; width]);
-            board.colors.push(vec![Color::WHITE; width]);
-        }
-        let old_level = level.0;
-        let get_score = match rows_to_remove.len() {
-            0 => ((level.0 + 1) * 0) as usize,
-            1 => ((level.0 + 1) * 40) as usize,
-            2 => ((level.0 + 1) * 100) as usize,
-            3 => ((level.0 + 1) * 300) as usize,
-            4 => ((level.0 + 1) * 1200) as usize,
-            _ => ((level.0 + 1) * 1200) as usize,
-        };
-        *cleared_lines = Some(cleared_lines.unwrap() + rows_to_remove.len() as u32);
-        score.0 += get_score;
-        let line_threshold = match old_level {
-            0..=8 => old_level * 10 + 10,
-            _ => std::cmp::min(std::cmp::max(100, old_level * 10 - 50), 200),
-        };
-        if cleared_lines.unwrap() >= line_threshold as u32 {
-            level.0 += 1;
-            *cleared_lines = Some(cleared_lines.unwrap() - line_threshold as u32);
-        }
-        level.0 = std::cmp::min(level.0, MAX_LEVEL); // Assign to level later :/
-        let new_level = level.0;
-        if old_level != new_level {
-            level.0 = new_level;
-        }
+fn hide_board(mut tiles: Query<&mut Visibility, With<BoardTile>>) {
+    for mut visibility in tiles.iter_mut() {
+        *visibility = Visibility::Hidden;
     }
 }
 
-#[derive(Component)]
-struct LevelModifier(usize);
-
-const MAX_PIECE_IN_BAG: usize = Piece::COUNT; // 7;
-
-#[derive(Resource)]
-struct Bag(Vec<Piece>);
-
-#[derive(Debug, Clone, Copy, EnumCountMacro)]
-enum Piece {
-    I,
-    O,
-    T,
-    S,
-    Z,
-    J,
-    L,
+fn update_sprite_colors(mut sprites: Query<(&mut Sprite, &mut BoardTileColor)>) {
+    for (mut sprite, color) in sprites.iter_mut() {
+        sprite.color = color.0;
+    }
 }
 
-impl Bag {
-    fn new() -> Self {
-        let mut bag = Self(vec![]);
-        bag.fill_bag();
-        bag
+#[allow(clippy::too_many_arguments)]
+fn update_board_sprites(
+    mut commands: Commands,
+    tetris: Res<Tetris>,
+    layout: Res<BoardLayout>,
+    board_tiles: Res<BoardTiles>,
+    next_queue_board: Res<NextQueueBoard>,
+    next_queue: Res<NextQueue>,
+    hold_board: Res<HoldBoard>,
+    ghost_board: Res<GhostPieceBoard>,
+    ghost_settings: Res<GhostPiece>,
+    current_piece_board: Res<CurrentPieceBoard>,
+) {
+    let board = tetris.0.board();
+    // A new game on a different sized board, the sprites for it are still on their way.
+    if BoardLayout::for_board(board) != *layout {
+        return;
     }
-
-    fn fill_bag(&mut self) {
-        let mut pieces = vec![
-            Piece::I,
-            Piece::O,
-            Piece::T,
-            Piece::S,
-            Piece::Z,
-            Piece::J,
-            Piece::L,
-        ];
-        let mut rng = rand::thread_rng();
-        for _ in 0..(MAX_PIECE_IN_BAG * 10) {
-            let index = rng.gen_range(0..pieces.len());
-            let index1 = rng.gen_range(0..pieces.len());
-            pieces.swap(index, index1);
+    // Rows on their way out flash white until the line clear delay is over.
+    let clearing = matches!(tetris.0.delay(), Some(Delay::LineClear(_)));
+    for row in 0..board_tiles.0.height {
+        for col in 0..board_tiles.0.width {
+            let entity = board_tiles.0.squares[row][col];
+            match board.get(col, row) {
+                Some(piece) => {
+                    // Faded out above the walls, it'll vanish if it isn't cleared back down.
+                    let color =
+                        if clearing && (0..board.width()).all(|col| board.is_filled(col, row)) {
+                            Color::WHITE
+                        } else if row < board.height() {
+                            piece_color(piece)
+                        } else {
+                            piece_color(piece).with_a(0.5)
+                        };
+                    commands.entity(entity).insert(Visibility::Visible);
+                    commands.entity(entity).insert(BoardTileColor(color));
+                }
+                None => {
+                    commands.entity(entity).insert(Visibility::Hidden);
+                }
+            }
         }
-        self.0 = pieces;
     }
 
-    fn next_piece(&mut self) -> Piece {
-        let ret = self.0.pop().unwrap();
-        if self.0.is_empty() {
-            self.fill_bag();
+    let next_pieces = tetris.0.next_pieces(next_queue.count);
+    for (index, grid) in next_queue_board.0.iter().enumerate() {
+        match next_pieces.get(index) {
+            Some(&piece) => grid.show_piece(&mut commands, piece, 0, Some(piece_color(piece))),
+            None => grid.hide(&mut commands),
         }
-        ret
-    }
-
-    fn peek(&self) -> Piece {
-        self.0.last().unwrap().clone()
     }
-}
-
-#[derive(Resource, Clone, Copy)]
-struct CurrentPiece {
-    piece: Piece,
-    position: IVec2,
-    rotation: i32,
-    placed: bool,
-}
 
-impl CurrentPiece {
-    fn new(piece: Piece) -> Self {
-        Self {
-            piece,
-            position: IVec2 {
-                x: BOARD_SIZE.x as i32 / 2,
-                y: BOARD_SIZE.y as i32 - 3,
-            },
-            rotation: 0,
-            placed: false,
+    match tetris.0.held_piece() {
+        Some(piece) => {
+            let color = if tetris.0.can_hold() {
+                piece_color(piece)
+            } else {
+                Color::GRAY
+            };
+            hold_board
+                .0
+                .show_piece(&mut commands, piece, 0, Some(color));
         }
+        None => hold_board.0.hide(&mut commands),
     }
 
-    fn width(&self) -> i32 {
-        piece_width(self.piece, self.rotation)
+    // Between pieces there's nothing in play to draw.
+    if tetris.0.delay().is_some() {
+        ghost_board.0.hide(&mut commands);
+        current_piece_board.0.hide(&mut commands);
+        return;
     }
-
-    fn get_color(self) -> Color {
-        match self.piece {
-            Piece::I => Color::rgb(0.5, 1.0, 1.0),
-            Piece::O => Color::rgb(1.0, 1.0, 0.5),
-            Piece::T => Color::rgb(1.0, 0.5, 1.0),
-            Piece::S => Color::rgb(0.5, 1.0, 0.5),
-            Piece::Z => Color::rgb(1.0, 0.5, 0.5),
-            Piece::J => Color::rgb(0.5, 0.5, 1.0),
-            Piece::L => Color::rgb(1.0, 0.7, 0.5),
-        }
+    let current_piece = tetris.0.current_piece();
+    let ghost = tetris.0.ghost_piece();
+    // Once the piece has landed the ghost would just sit underneath it.
+    if ghost_settings.visible && ghost.position != current_piece.position {
+        ghost_board.0.show_piece(
+            &mut commands,
+            ghost.piece,
+            ghost.rotation,
+            Some(piece_color(ghost.piece).with_a(ghost_settings.opacity)),
+        );
+    } else {
+        ghost_board.0.hide(&mut commands);
     }
-}
 
-fn piece_width(piece: Piece, rotation: i32) -> i32 {
-    get_piece_meat_positions(piece, IVec2::ZERO, rotation)
-        .iter()
-        .map(|x| x.x)
-        .max()
-        .unwrap()
-        + 1
+    current_piece_board.0.show_piece(
+        &mut commands,
+        current_piece.piece,
+        current_piece.rotation,
+        Some(piece_color(current_piece.piece)),
+    );
 }
 
-fn place_piece(
-    mut current_piece: ResMut<CurrentPiece>,
-    mut board: ResMut<Board>,
-    mut display_board: ResMut<DisplayBoard>,
-    mut current_piece_board: ResMut<CurrentPieceBoard>,
-    mut bag: ResMut<Bag>,
-) {
-    if current_piece.placed {
-        place_piece_in_array(
-            current_piece.piece,
-            current_piece.position,
-            current_piece.rotation,
-            &mut board,
-            Some(current_piece.get_color()),
-        );
-        *current_piece = CurrentPiece::new(bag.next_piece());
-        current_piece_board.0.board =
-            vec![vec![false; current_piece_board.0.width]; current_piece_board.0.height];
-        place_piece_in_array(
-            current_piece.piece,
-            IVec2::ZERO,
-            current_piece.rotation,
-            &mut current_piece_board.0,
-            None,
-        );
-        display_board.0.board = vec![vec![false; display_board.0.width]; display_board.0.height];
-        place_piece_in_array(bag.peek(), IVec2::ZERO, 0, &mut display_board.0, None);
+fn render_score(mut score_node: Query<&mut Text, With<ScoreDisplay>>, tetris: Res<Tetris>) {
+    for mut text in score_node.iter_mut() {
+        text.sections[0].value = format!("{}", tetris.0.score());
     }
 }
-fn check_piece_obstructed(
-    piece: Piece,
-    offset: IVec2,
-    rotation: i32,
-    array: &mut [&mut [bool]],
-) -> bool {
-    let meat = get_piece_meat_positions(piece, offset, rotation);
-    for pos in meat.iter() {
-        if pos.x < 0 || pos.x >= BOARD_SIZE.x as i32 || pos.y < 0 {
-            return true;
-        }
-        if pos.y >= BOARD_SIZE.y as i32 {
-            continue;
-        }
-        if array[pos.y as usize][pos.x as usize] {
-            return true;
-        }
-    }
-    false
+
+/// Minutes, seconds and milliseconds, like `1:02.345`.
+fn format_time(time: Duration) -> String {
+    let millis = time.as_millis();
+    format!(
+        "{}:{:02}.{:03}",
+        millis / 60_000,
+        millis / 1000 % 60,
+        millis % 1000
+    )
 }
 
-fn place_piece_in_array(
-    piece: Piece,
-    offset: IVec2,
-    rotation: i32,
-    board: &mut Board,
-    color: Option<Color>,
-) {
-    let color = match color {
-        Some(c) => c,
-        None => Color::WHITE,
+/// The clock for modes that race it, counting up in Sprint and down to the end of an Ultra.
+fn render_timer(mut timer_node: Query<&mut Text, With<TimerDisplay>>, tetris: Res<Tetris>) {
+    let game = &tetris.0;
+    let value = match game.rules().goal {
+        Goal::Endless => String::new(),
+        Goal::Lines(goal) => format!(
+            "{}\n{}/{goal} lines",
+            format_time(game.elapsed()),
+            game.lines().min(goal)
+        ),
+        Goal::Time(limit) => format_time(limit.saturating_sub(game.elapsed())),
     };
-    let meat = get_piece_meat_positions(piece, offset, rotation);
-    for pos in meat.iter() {
-        board.board[pos.y as usize][pos.x as usize] = true;
-    }
-
-    for pos in meat.iter() {
-        board.colors[pos.y as usize][pos.x as usize] = color;
+    for mut text in timer_node.iter_mut() {
+        text.sections[0].value.clone_from(&value);
     }
 }
 
-fn get_piece_meat_positions(piece: Piece, offset: IVec2, rotation: i32) -> [IVec2; 4] {
-    let x = offset.x as i32;
-    let y = offset.y as i32;
-    match piece {
-        Piece::I => match rotation {
-            0 | 2 => [
-                IVec2 { x: x + 0, y: y + 0 },
-                IVec2 { x: x + 1, y: y + 0 },
-                IVec2 { x: x + 2, y: y + 0 },
-                IVec2 { x: x + 3, y: y + 0 },
-            ],
-            1 | 3 => [
-                IVec2 { x: x + 0, y: y + 0 },
-                IVec2 { x: x + 0, y: y + 1 },
-                IVec2 { x: x + 0, y: y + 2 },
-                IVec2 { x: x + 0, y: y + 3 },
-            ],
-            _ => unreachable!(),
-        },
-        Piece::O => [
-            IVec2 { x: x + 0, y: y + 0 },
-            IVec2 { x: x + 0, y: y + 1 },
-            IVec2 { x: x + 1, y: y + 0 },
-            IVec2 { x: x + 1, y: y + 1 },
-        ],
-        Piece::T => match rotation {
-            0 => [
-                IVec2 { x: x + 0, y: y + 1 },
-                IVec2 { x: x + 1, y: y + 1 },
-                IVec2 { x: x + 1, y: y + 0 },
-                IVec2 { x: x + 1, y: y + 2 },
-            ],
-            1 => [
-                IVec2 { x: x + 1, y: y + 0 },
-                IVec2 { x: x + 1, y: y + 1 },
-                IVec2 { x: x + 0, y: y + 1 },
-                IVec2 { x: x + 2, y: y + 1 },
-            ],
-            2 => [
-                IVec2 { x: x + 1, y: y + 1 },
-                IVec2 { x: x + 0, y: y + 1 },
-                IVec2 { x: x + 0, y: y + 0 },
-                IVec2 { x: x + 0, y: y + 2 },
-            ],
-            3 => [
-                IVec2 { x: x + 1, y: y + 1 },
-                IVec2 { x: x + 1, y: y + 0 },
-                IVec2 { x: x + 0, y: y + 0 },
-                IVec2 { x: x + 2, y: y + 0 },
-            ],
-            _ => unreachable!(),
-        },
-        Piece::S => match rotation {
-            0 | 2 => [
-                IVec2 { x: x + 0, y: y + 0 },
-                IVec2 { x: x + 0, y: y + 1 },
-                IVec2 { x: x + 1, y: y + 1 },
-                IVec2 { x: x + 1, y: y + 2 },
-            ],
-            1 | 3 => [
-                IVec2 { x: x + 0, y: y + 1 },
-                IVec2 { x: x + 1, y: y + 1 },
-                IVec2 { x: x + 1, y: y + 0 },
-                IVec2 { x: x + 2, y: y + 0 },
-            ],
-            _ => unreachable!(),
-        },
-        Piece::Z => match rotation {
-            0 | 2 => [
-                IVec2 { x: x + 1, y: y + 0 },
-                IVec2 { x: x + 0, y: y + 1 },
-                IVec2 { x: x + 1, y: y + 1 },
-                IVec2 { x: x + 0, y: y + 2 },
-            ],
-            1 | 3 => [
-                IVec2 { x: x + 0, y: y + 0 },
-                IVec2 { x: x + 1, y: y + 1 },
-                IVec2 { x: x + 1, y: y + 0 },
-                IVec2 { x: x + 2, y: y + 1 },
-            ],
-            _ => unreachable!(),
-        },
-        Piece::J => match rotation {
-            0 => [
-                IVec2 { x: x + 0, y: y + 0 },
-                IVec2 { x: x + 0, y: y + 1 },
-                IVec2 { x: x + 1, y: y + 1 },
-                IVec2 { x: x + 2, y: y + 1 },
-            ],
-            1 => [
-                IVec2 { x: x + 0, y: y + 0 },
-                IVec2 { x: x + 0, y: y + 1 },
-                IVec2 { x: x + 0, y: y + 2 },
-                IVec2 { x: x + 1, y: y + 0 },
-            ],
-            2 => [
-                IVec2 { x: x + 0, y: y + 0 },
-                IVec2 { x: x + 1, y: y + 0 },
-                IVec2 { x: x + 2, y: y + 0 },
-                IVec2 { x: x + 2, y: y + 1 },
-            ],
-            3 => [
-                IVec2 { x: x + 1, y: y + 0 },
-                IVec2 { x: x + 1, y: y + 1 },
-                IVec2 { x: x + 1, y: y + 2 },
-                IVec2 { x: x + 0, y: y + 2 },
-            ],
-            _ => unreachable!(),
-        },
-        Piece::L => match rotation {
-            0 => [
-                IVec2 { x: x + 0, y: y + 1 },
-                IVec2 { x: x + 0, y: y + 0 },
-                IVec2 { x: x + 1, y: y + 0 },
-                IVec2 { x: x + 2, y: y + 0 },
-            ],
-            1 => [
-                IVec2 { x: x + 1, y: y + 0 },
-                IVec2 { x: x + 1, y: y + 1 },
-                IVec2 { x: x + 1, y: y + 2 },
-                IVec2 { x: x + 0, y: y + 0 },
-            ],
-            2 => [
-                IVec2 { x: x + 0, y: y + 1 },
-                IVec2 { x: x + 1, y: y + 1 },
-                IVec2 { x: x + 2, y: y + 1 },
-                IVec2 { x: x + 2, y: y + 0 },
-            ],
-            3 => [
-                IVec2 { x: x + 0, y: y + 0 },
-                IVec2 { x: x + 0, y: y + 1 },
-                IVec2 { x: x + 0, y: y + 2 },
-                IVec2 { x: x + 1, y: y + 2 },
-            ],
-            _ => unreachable!(),
-        },
+fn render_level(mut level_node: Query<&mut Text, With<LevelDisplay>>, tetris: Res<Tetris>) {
+    for mut text in level_node.iter_mut() {
+        text.sections[0].value = format!("Level: {}", tetris.0.level());
     }
 }
 
+/// How long a clear announcement stays up, in seconds.
+const ANNOUNCEMENT_TIME: f32 = 1.5;
+/// How far an announcement drifts up while it fades, in board cells.
+const ANNOUNCEMENT_RISE: f32 = 3.0;
+/// Text is laid out at this size then scaled down to board cells so it stays sharp.
+const ANNOUNCEMENT_FONT_SIZE: f32 = 64.0;
+
+/// "TETRIS", "COMBO x3" and friends floating up over the board after a clear.
 #[derive(Component)]
-struct Collider;
+struct Announcement {
+    age: f32,
+}
 
-fn render_score(mut score_node: Query<&mut Text, With<ScoreDisplay>>, score: Res<Score>) {
-    for mut text in score_node.iter_mut() {
-        text.sections[0].value = format!("{}", score.0);
+fn announce_clears(
+    mut commands: Commands,
+    mut game_events: EventReader<GameEvent>,
+    asset_server: Res<AssetServer>,
+    layout: Res<BoardLayout>,
+) {
+    let font = asset_server.load("fonts/UbuntuMonoNerdFontCompleteMono.ttf");
+    for event in game_events.iter() {
+        let GameEvent::Cleared { action, points } = event else {
+            continue;
+        };
+        let mut lines = vec![];
+        if action.back_to_back {
+            lines.push(("BACK-TO-BACK".to_string(), 0.6, Color::ORANGE));
+        }
+        if let Some(name) = action.name() {
+            lines.push((name, 1.0, Color::WHITE));
+        }
+        if action.combo > 0 {
+            lines.push((format!("COMBO x{}", action.combo), 0.7, Color::CYAN));
+        }
+        if action.perfect_clear {
+            lines.push(("PERFECT CLEAR".to_string(), 0.9, Color::GOLD));
+        }
+        lines.push((format!("+{points}"), 0.6, Color::BEIGE));
+
+        let sections = lines
+            .into_iter()
+            .enumerate()
+            .map(|(index, (text, size, color))| {
+                let text = if index == 0 {
+                    text
+                } else {
+                    format!("\n{text}")
+                };
+                TextSection::new(
+                    text,
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: ANNOUNCEMENT_FONT_SIZE * size,
+                        color,
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_sections(sections).with_alignment(TextAlignment::Center),
+                transform: Transform {
+                    translation: layout.board_centre().extend(10.0),
+                    scale: Vec3::splat(1.0 / ANNOUNCEMENT_FONT_SIZE),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Announcement { age: 0.0 },
+        ));
     }
 }
 
-fn render_level(mut level_node: Query<&mut Text, With<LevelDisplay>>, level: Res<Level>) {
-    for mut text in level_node.iter_mut() {
-        text.sections[0].value = format!("Level: {}", level.0);
+/// Slides announcements up and fades them out, then gets rid of them.
+fn animate_announcements(
+    mut commands: Commands,
+    time: Res<Time>,
+    layout: Res<BoardLayout>,
+    mut announcements: Query<(Entity, &mut Announcement, &mut Transform, &mut Text)>,
+) {
+    for (entity, mut announcement, mut transform, mut text) in announcements.iter_mut() {
+        announcement.age += time.delta_seconds();
+        let progress = announcement.age / ANNOUNCEMENT_TIME;
+        if progress >= 1.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        // Eases out so it pops up quickly then hangs there while it fades.
+        let rise = 1.0 - (1.0 - progress).powi(3);
+        transform.translation.y = layout.board_centre().y + rise * ANNOUNCEMENT_RISE;
+        let alpha = 1.0 - progress * progress;
+        for section in text.sections.iter_mut() {
+            section.style.color.set_a(alpha);
+        }
     }
 }
 
@@ -523,20 +437,15 @@ struct ScoreDisplay;
 #[derive(Component)]
 struct LevelDisplay;
 
-fn setup_board(
-    mut commands: Commands,
-    mut bag: ResMut<Bag>,
-    score: ResMut<Score>,
-    level: ResMut<Level>,
-    mut board: ResMut<Board>,
-    mut display_board: ResMut<DisplayBoard>,
-    mut current_piece_board: ResMut<CurrentPieceBoard>,
-    asset_server: Res<AssetServer>,
-) {
-    let piece = bag.next_piece();
-    let current_piece = CurrentPiece::new(piece);
-    commands.insert_resource(current_piece);
+/// The clock, for modes that have one.
+#[derive(Component)]
+struct TimerDisplay;
+
+/// How a Sprint is doing against the best run.
+#[derive(Component)]
+struct PaceDisplay;
 
+fn setup_hud(mut commands: Commands, tetris: Res<Tetris>, asset_server: Res<AssetServer>) {
     commands
         .spawn(NodeBundle {
             style: Style {
@@ -559,12 +468,11 @@ fn setup_board(
                 .with_children(|left_column| {
                     left_column.spawn((
                         TextBundle::from_section(
-                            format!("{}", score.0),
+                            format!("{}", tetris.0.score()),
                             TextStyle {
                                 font_size: 125.0, // TODO: Notify bevy of this kaka
                                 color: Color::WHITE,
                                 font: asset_server.load("fonts/UbuntuMonoNerdFontCompleteMono.ttf"), // It is path-ing agnostic
-                                ..Default::default()
                             },
                         )
                         .with_style(Style {
@@ -577,12 +485,45 @@ fn setup_board(
 
                     left_column.spawn((
                         TextBundle::from_section(
-                            format!("Level: {}", level.0),
+                            "",
+                            TextStyle {
+                                font_size: 60.0,
+                                color: Color::WHITE,
+                                font: asset_server.load("fonts/UbuntuMonoNerdFontCompleteMono.ttf"),
+                            },
+                        )
+                        .with_style(Style {
+                            margin: UiRect::all(Val::Px(10.0)),
+                            ..Default::default()
+                        }),
+                        Label,
+                        TimerDisplay,
+                    ));
+
+                    left_column.spawn((
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font_size: 60.0,
+                                color: Color::WHITE,
+                                font: asset_server.load("fonts/UbuntuMonoNerdFontCompleteMono.ttf"),
+                            },
+                        )
+                        .with_style(Style {
+                            margin: UiRect::all(Val::Px(10.0)),
+                            ..Default::default()
+                        }),
+                        Label,
+                        PaceDisplay,
+                    ));
+
+                    left_column.spawn((
+                        TextBundle::from_section(
+                            format!("Level: {}", tetris.0.level()),
                             TextStyle {
                                 font_size: 80.0, // TODO: Notify bevy of this kaka
                                 color: Color::BEIGE,
                                 font: asset_server.load("fonts/UbuntuMonoNerdFontCompleteMono.ttf"), // It is path-ing agnostic
-                                ..Default::default()
                             },
                         )
                         .with_style(Style {
@@ -601,11 +542,50 @@ fn setup_board(
                 ..Default::default()
             });
         });
+}
+
+/// Builds the board, walls, queue and hold sprites to fit the game's board, and again whenever a
+/// new game comes with a different sized one.
+#[allow(clippy::too_many_arguments)]
+fn build_board_view(
+    mut commands: Commands,
+    tetris: Res<Tetris>,
+    mut layout: ResMut<BoardLayout>,
+    mut board_tiles: ResMut<BoardTiles>,
+    mut next_queue_board: ResMut<NextQueueBoard>,
+    mut hold_board: ResMut<HoldBoard>,
+    mut ghost_board: ResMut<GhostPieceBoard>,
+    mut current_piece_board: ResMut<CurrentPieceBoard>,
+    asset_server: Res<AssetServer>,
+    views: Query<Entity, With<BoardView>>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection)>,
+    mut backgrounds: Query<
+        (&mut Sprite, &mut Transform, &Background),
+        Without<OrthographicProjection>,
+    >,
+) {
+    let board = tetris.0.board();
+    let new_layout = BoardLayout::for_board(board);
+    if new_layout == *layout {
+        return;
+    }
+    *layout = new_layout;
+    for view in views.iter() {
+        commands.entity(view).despawn_recursive();
+    }
+    board_tiles.0 = TileGrid::new(board.width(), board.height() + layout.buffer_rows);
+    for (mut camera, mut projection) in cameras.iter_mut() {
+        fit_camera(&layout, &mut camera, &mut projection);
+    }
+    for (mut sprite, mut transform, background) in backgrounds.iter_mut() {
+        fit_background(&layout, background, &mut sprite, &mut transform);
+    }
+
     let block_image = asset_server.load("textures/block.png");
 
-    for col_index in 0..board.width {
-        for row_index in 0..board.height {
-            board.squares[row_index][col_index] = commands
+    for col_index in 0..board_tiles.0.width {
+        for row_index in 0..board_tiles.0.height {
+            board_tiles.0.squares[row_index][col_index] = commands
                 .spawn((
                     SpriteBundle {
                         sprite: Sprite {
@@ -613,7 +593,7 @@ fn setup_board(
                             anchor: bevy::sprite::Anchor::BottomLeft,
                             ..Default::default()
                         },
-                        texture: block_image.clone().into(),
+                        texture: block_image.clone(),
                         transform: Transform {
                             translation: Vec3::new(
                                 (col_index as f32) + (BOARD_ORIGIN.x),
@@ -630,25 +610,66 @@ fn setup_board(
                         ..Default::default()
                     },
                     BoardTile,
+                    BoardView,
                 ))
                 .id();
         }
     }
 
-    for col_index in 0..display_board.0.width {
-        for row_index in 0..display_board.0.height {
-            display_board.0.squares[row_index][col_index] = commands
+    // Top to bottom down the right of the board. Pieces in their spawn state only use the
+    // middle rows of their box, so the boxes can overlap by a row.
+    let queue_x = BOARD_ORIGIN.x + layout.size.x + 1.;
+    let queue_top = BOARD_ORIGIN.y + layout.size.y - 4.;
+    for (index, grid) in next_queue_board.0.iter_mut().enumerate() {
+        let grid_y = queue_top - (index * NEXT_QUEUE_SPACING) as f32;
+        for col_index in 0..grid.width {
+            for row_index in 0..grid.height {
+                grid.squares[row_index][col_index] = commands
+                    .spawn((
+                        SpriteBundle {
+                            sprite: Sprite {
+                                custom_size: Some(Vec2::ONE),
+                                anchor: bevy::sprite::Anchor::BottomLeft,
+                                ..Default::default()
+                            },
+                            texture: block_image.clone(),
+                            transform: Transform {
+                                translation: Vec3::new(
+                                    col_index as f32 + queue_x,
+                                    row_index as f32 + grid_y,
+                                    0.0,
+                                ),
+                                scale: Vec3 {
+                                    x: 0.95,
+                                    y: 0.95,
+                                    z: 0.95,
+                                },
+                                ..default()
+                            },
+                            visibility: Visibility::Hidden,
+                            ..Default::default()
+                        },
+                        BoardTile,
+                        BoardView,
+                    ))
+                    .id();
+            }
+        }
+    }
+
+    for col_index in 0..hold_board.0.width {
+        for row_index in 0..hold_board.0.height {
+            hold_board.0.squares[row_index][col_index] = commands
                 .spawn((
                     SpriteBundle {
                         sprite: Sprite {
                             custom_size: Some(Vec2::ONE),
-                            color: Color::ORANGE_RED,
                             anchor: bevy::sprite::Anchor::BottomLeft,
                             ..Default::default()
                         },
-                        texture: block_image.clone().into(),
+                        texture: block_image.clone(),
                         transform: Transform {
-                            translation: Vec3::new(col_index as f32, row_index as f32 + 5., 0.0),
+                            translation: Vec3::new(col_index as f32, row_index as f32 + 10., 0.0),
                             scale: Vec3 {
                                 x: 0.95,
                                 y: 0.95,
@@ -656,9 +677,11 @@ fn setup_board(
                             },
                             ..default()
                         },
+                        visibility: Visibility::Hidden,
                         ..Default::default()
                     },
                     BoardTile,
+                    BoardView,
                 ))
                 .id();
         }
@@ -676,8 +699,57 @@ fn setup_board(
             VisibilityBundle {
                 ..Default::default()
             },
+            BoardView,
         ))
         .with_children(|board_parent| {
+            board_parent
+                .spawn((
+                    TransformBundle {
+                        local: Transform {
+                            translation: Vec3::ZERO,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    GhostPieceTransform,
+                    VisibilityBundle {
+                        ..Default::default()
+                    },
+                ))
+                .with_children(|ghost_parent| {
+                    for col_index in 0..ghost_board.0.width {
+                        for row_index in 0..ghost_board.0.height {
+                            ghost_board.0.squares[row_index][col_index] = ghost_parent
+                                .spawn((
+                                    SpriteBundle {
+                                        sprite: Sprite {
+                                            custom_size: Some(Vec2::ONE),
+                                            anchor: bevy::sprite::Anchor::BottomLeft,
+                                            ..Default::default()
+                                        },
+                                        texture: block_image.clone(),
+                                        transform: Transform {
+                                            translation: Vec3::new(
+                                                col_index as f32,
+                                                row_index as f32,
+                                                0.0,
+                                            ),
+                                            scale: Vec3 {
+                                                x: 0.95,
+                                                y: 0.95,
+                                                z: 0.95,
+                                            },
+                                            ..default()
+                                        },
+                                        visibility: Visibility::Hidden,
+                                        ..Default::default()
+                                    },
+                                    BoardTile,
+                                ))
+                                .id();
+                        }
+                    }
+                });
             board_parent
                 .spawn((
                     TransformBundle {
@@ -704,7 +776,7 @@ fn setup_board(
                                                 anchor: bevy::sprite::Anchor::BottomLeft,
                                                 ..Default::default()
                                             },
-                                            texture: block_image.clone().into(),
+                                            texture: block_image.clone(),
                                             transform: Transform {
                                                 translation: Vec3::new(
                                                     col_index as f32,
@@ -728,231 +800,195 @@ fn setup_board(
                 });
         });
 
-    current_piece_board.0.board = vec![vec![false; display_board.0.width]; display_board.0.height];
-    place_piece_in_array(piece, IVec2::ZERO, 0, &mut current_piece_board.0, None);
-    display_board.0.board = vec![vec![false; display_board.0.width]; display_board.0.height];
-    place_piece_in_array(bag.peek(), IVec2::ZERO, 0, &mut display_board.0, None);
-    for row in 0..board.height + 1 {
-        commands.spawn(SpriteBundle {
-            sprite: Sprite {
-                custom_size: Some(Vec2::ONE),
-                color: Color::rgb(0.3, 0.4, 0.5),
-                anchor: bevy::sprite::Anchor::BottomLeft,
-                ..Default::default()
-            },
-            texture: block_image.clone().into(),
-            transform: Transform {
-                translation: Vec3::new(-1. + BOARD_ORIGIN.x, row as f32, 0.0),
-                scale: Vec3 {
-                    x: 0.95,
-                    y: 0.95,
-                    z: 0.95,
+    for row in 0..board_tiles.0.height + 1 {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::ONE),
+                    color: Color::rgb(0.3, 0.4, 0.5),
+                    anchor: bevy::sprite::Anchor::BottomLeft,
+                    ..Default::default()
+                },
+                texture: block_image.clone(),
+                transform: Transform {
+                    translation: Vec3::new(-1. + BOARD_ORIGIN.x, row as f32, 0.0),
+                    scale: Vec3 {
+                        x: 0.95,
+                        y: 0.95,
+                        z: 0.95,
+                    },
+                    ..default()
                 },
-                ..default()
-            },
-            ..Default::default()
-        });
-        commands.spawn(SpriteBundle {
-            sprite: Sprite {
-                custom_size: Some(Vec2::ONE),
-                color: Color::rgb(0.3, 0.4, 0.5),
-                anchor: bevy::sprite::Anchor::BottomLeft,
                 ..Default::default()
             },
-            texture: block_image.clone().into(),
-            transform: Transform {
-                translation: Vec3::new(BOARD_ORIGIN.x + BOARD_SIZE.x, row as f32, 0.0),
-                scale: Vec3 {
-                    x: 0.95,
-                    y: 0.95,
-                    z: 0.95,
+            BoardView,
+        ));
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::ONE),
+                    color: Color::rgb(0.3, 0.4, 0.5),
+                    anchor: bevy::sprite::Anchor::BottomLeft,
+                    ..Default::default()
                 },
-                ..default()
+                texture: block_image.clone(),
+                transform: Transform {
+                    translation: Vec3::new(BOARD_ORIGIN.x + layout.size.x, row as f32, 0.0),
+                    scale: Vec3 {
+                        x: 0.95,
+                        y: 0.95,
+                        z: 0.95,
+                    },
+                    ..default()
+                },
+                ..Default::default()
             },
-            ..Default::default()
-        });
+            BoardView,
+        ));
     }
 
-    for col_pos in 0..BOARD_SIZE.x as i32 {
-        commands.spawn(SpriteBundle {
-            sprite: Sprite {
-                custom_size: Some(Vec2::ONE),
-                color: Color::rgb(0.3, 0.4, 0.5),
-                anchor: bevy::sprite::Anchor::BottomLeft,
-                ..Default::default()
-            },
-            texture: block_image.clone().into(),
-            transform: Transform {
-                translation: Vec3::new(col_pos as f32 + BOARD_ORIGIN.x, -1. + BOARD_ORIGIN.y, 0.0),
-                scale: Vec3 {
-                    x: 0.95,
-                    y: 0.95,
-                    z: 0.95,
+    for col_pos in 0..board_tiles.0.width {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::ONE),
+                    color: Color::rgb(0.3, 0.4, 0.5),
+                    anchor: bevy::sprite::Anchor::BottomLeft,
+                    ..Default::default()
+                },
+                texture: block_image.clone(),
+                transform: Transform {
+                    translation: Vec3::new(
+                        col_pos as f32 + BOARD_ORIGIN.x,
+                        -1. + BOARD_ORIGIN.y,
+                        0.0,
+                    ),
+                    scale: Vec3 {
+                        x: 0.95,
+                        y: 0.95,
+                        z: 0.95,
+                    },
+                    ..default()
                 },
-                ..default()
+                ..Default::default()
             },
-            ..Default::default()
-        });
+            BoardView,
+        ));
     }
 }
 
+/// Keys pressed since the last tick. Frames and ticks don't line up, so presses pile up here until
+/// a tick uses them and none get lost or counted twice.
+#[derive(Resource, Default)]
+struct LatchedInput(GameInput);
+
 fn keyboard_input(
     keys: Res<Input<KeyCode>>,
-    mut bag: ResMut<Bag>,
-    mut level: ResMut<Level>,
-    mut score: ResMut<Score>,
-    mut current_piece: ResMut<CurrentPiece>,
-    mut current_piece_board: ResMut<CurrentPieceBoard>,
-    mut board: ResMut<Board>,
+    mut latched: ResMut<LatchedInput>,
+    mut restarts: EventWriter<RestartGame>,
 ) {
-    let mut redraw_piece_board = false;
-    let current_piece_snapshot = current_piece.clone();
-    if keys.just_pressed(KeyCode::Q) {
-        let new_rotation = (current_piece.rotation + 1) % 4;
-        let current_width = piece_width(current_piece.piece, current_piece.rotation);
-        let new_width = piece_width(current_piece.piece, new_rotation);
-        let offset = (current_width - new_width) / 2;
-        current_piece.position.x = current_piece.position.x + offset;
-        current_piece.rotation = new_rotation;
-        redraw_piece_board = true;
-    }
-    if keys.just_pressed(KeyCode::E) {
-        let new_rotation = (current_piece.rotation + 3) % 4;
-        let current_width = piece_width(current_piece.piece, current_piece.rotation);
-        let new_width = piece_width(current_piece.piece, new_rotation);
-        let offset = (current_width - new_width) / 2;
-        current_piece.position.x = current_piece.position.x + offset;
-        current_piece.rotation = new_rotation;
-        redraw_piece_board = true;
-    }
-    if keys.any_just_pressed([KeyCode::Left, KeyCode::A]) {
-        current_piece.position.x = current_piece.position.x - 1;
-    }
-    if keys.any_just_pressed([KeyCode::Right, KeyCode::D]) {
-        current_piece.position.x = current_piece.position.x + 1;
-    }
-    if keys.just_pressed(KeyCode::Down) {}
-    if keys.any_just_pressed([KeyCode::Up, KeyCode::W]) {
-        //keys.just_pressed(KeyCode::Up) {
-        current_piece.position = IVec2::ZERO;
-        current_piece.placed = true;
-    }
-    if keys.just_pressed(KeyCode::Space) {
-        while !check_piece_obstructed(
-            current_piece.piece,
-            current_piece.position + IVec2::NEG_Y,
-            current_piece.rotation,
-            board
-                .board
-                .iter_mut()
-                .map(|x| x.as_mut_slice())
-                .collect::<Vec<_>>()
-                .as_mut_slice(),
-        ) {
-            current_piece.position.y -= 1;
-        }
-    }
+    let input = GameInput {
+        rotate_ccw: keys.just_pressed(KeyCode::Q),
+        rotate_ccw_held: keys.pressed(KeyCode::Q),
+        rotate_cw: keys.just_pressed(KeyCode::E),
+        rotate_cw_held: keys.pressed(KeyCode::E),
+        rotate_180: keys.just_pressed(KeyCode::R),
+        left: keys.any_just_pressed([KeyCode::Left, KeyCode::A]),
+        left_held: keys.any_pressed([KeyCode::Left, KeyCode::A]),
+        right: keys.any_just_pressed([KeyCode::Right, KeyCode::D]),
+        right_held: keys.any_pressed([KeyCode::Right, KeyCode::D]),
+        soft_drop: keys.pressed(KeyCode::Down),
+        hard_drop: keys.just_pressed(KeyCode::Space),
+        hold: keys.any_just_pressed([KeyCode::C, KeyCode::LShift]),
+        hold_held: keys.any_pressed([KeyCode::C, KeyCode::LShift]),
+    };
     if keys.just_pressed(KeyCode::Escape) {
-        for row in 0..board.height {
-            for col in 0..board.width {
-                board.board[row][col] = false;
-            }
-        }
-        level.0 = 0;
-        score.0 = 0;
-        *current_piece = CurrentPiece::new(bag.next_piece());
-    }
-    let piece_width = current_piece.width();
-    if current_piece.position.x < 0 {
-        current_piece.position.x = 0;
-    }
-    if (current_piece.position.x + piece_width) >= BOARD_SIZE.x as i32 {
-        current_piece.position.x = BOARD_SIZE.x as i32 - piece_width;
+        restarts.send(RestartGame::default());
+        return;
     }
+    latched.0 = latched.0.latch(input);
+}
 
-    if check_piece_obstructed(
-        current_piece.piece,
-        current_piece.position,
-        current_piece.rotation,
-        board
-            .board
-            .iter_mut()
-            .map(|x| x.as_mut_slice())
-            .collect::<Vec<_>>()
-            .as_mut_slice(),
-    ) {
-        *current_piece = current_piece_snapshot;
-        current_piece.placed = true;
-    }
+/// Most ticks one frame can catch up on. After a longer stall the rest of the time is dropped, so
+/// the game picks up where it was rather than jumping ahead.
+const MAX_TICKS_PER_FRAME: u32 = 5;
 
-    if redraw_piece_board {
-        current_piece_board.0.board =
-            vec![vec![false; current_piece_board.0.width]; current_piece_board.0.height];
-        place_piece_in_array(
-            current_piece.piece,
-            IVec2::ZERO,
-            current_piece.rotation,
-            &mut current_piece_board.0,
-            Some(current_piece.get_color()),
-        );
+/// Frame time not yet spent on ticks.
+#[derive(Resource, Default)]
+struct TickClock(Duration);
+
+/// Runs the game one fixed 60 Hz tick at a time, however fast frames are coming. Presses go to the
+/// first tick, the rest of the frame's ticks only see what's held.
+fn tick_game(
+    time: Res<Time>,
+    mut clock: ResMut<TickClock>,
+    mut tetris: ResMut<Tetris>,
+    mut latched: ResMut<LatchedInput>,
+    mut game_events: EventWriter<GameEvent>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    clock.0 = (clock.0 + time.delta()).min(TICK * MAX_TICKS_PER_FRAME);
+    while clock.0 >= TICK {
+        clock.0 -= TICK;
+        let input = latched.0;
+        latched.0 = input.held();
+        game_events.send_batch(tetris.0.tick(input));
+        if tetris.0.is_over() {
+            next_state.set(AppState::GameOver);
+            break;
+        }
     }
 }
 
 fn update_piece_display_position(
-    current_piece: Res<CurrentPiece>,
+    tetris: Res<Tetris>,
     mut current_piece_transform: Query<&mut Transform, With<CurrentPieceTransform>>,
+    mut ghost_piece_transform: Query<
+        &mut Transform,
+        (With<GhostPieceTransform>, Without<CurrentPieceTransform>),
+    >,
 ) {
     let mut current_piece_transform = current_piece_transform.single_mut();
+    let current_piece = tetris.0.current_piece();
 
     current_piece_transform.translation.y = current_piece.position.y as f32;
     current_piece_transform.translation.x = current_piece.position.x as f32;
+
+    let mut ghost_piece_transform = ghost_piece_transform.single_mut();
+    let ghost = tetris.0.ghost_piece();
+
+    ghost_piece_transform.translation.y = ghost.position.y as f32;
+    ghost_piece_transform.translation.x = ghost.position.x as f32;
 }
 
-fn update(
-    keys: Res<Input<KeyCode>>,
-    mut current_piece: ResMut<CurrentPiece>,
-    mut board: ResMut<Board>,
-    mut timer: Local<Option<Timer>>,
-    mut last_level: Local<usize>,
-    level: Res<Level>,
-    time: Res<Time>,
-) {
-    const BASE_TIME: f32 = 0.8;
-    const MIN_TIME: f32 = 0.1;
-    if timer.is_none() {
-        *last_level = 0;
-        *timer = Some(Timer::from_seconds(BASE_TIME, TimerMode::Repeating));
-    } else if *last_level != level.0 || keys.just_released(KeyCode::Down) {
-        let range = BASE_TIME - MIN_TIME;
-        let level_percentage = level.0 as f32 / MAX_LEVEL as f32;
-        let exponential_range = level_percentage * level_percentage;
-        let interval = BASE_TIME - (range * exponential_range);
-        let elapsed = timer.as_mut().unwrap().elapsed();
-        *timer = Some(Timer::from_seconds(interval, TimerMode::Repeating));
-        timer.as_mut().unwrap().set_elapsed(elapsed);
-        *last_level = level.0;
+/// How the hard drop landing spot is drawn.
+#[derive(Resource)]
+struct GhostPiece {
+    visible: bool,
+    opacity: f32,
+}
+
+impl Default for GhostPiece {
+    fn default() -> Self {
+        Self {
+            visible: true,
+            opacity: 0.3,
+        }
+    }
+}
+
+/// How much each press of [ or ] fades or firms up the ghost.
+const GHOST_OPACITY_STEP: f32 = 0.1;
+
+fn toggle_ghost(keys: Res<Input<KeyCode>>, mut ghost: ResMut<GhostPiece>) {
+    if keys.just_pressed(KeyCode::G) {
+        ghost.visible = !ghost.visible;
     }
-    if keys.just_pressed(KeyCode::Down) {
-        let elapsed = timer.as_mut().unwrap().elapsed();
-        *timer = Some(Timer::from_seconds(MIN_TIME, TimerMode::Repeating));
-        timer.as_mut().unwrap().set_elapsed(elapsed);
+    if keys.just_pressed(KeyCode::LBracket) {
+        ghost.opacity = (ghost.opacity - GHOST_OPACITY_STEP).max(GHOST_OPACITY_STEP);
     }
-    if timer.as_mut().unwrap().tick(time.delta()).just_finished() {
-        if !check_piece_obstructed(
-            current_piece.piece,
-            current_piece.position + IVec2::NEG_Y,
-            current_piece.rotation,
-            board
-                .board
-                .iter_mut()
-                .map(|x| x.as_mut_slice())
-                .collect::<Vec<_>>()
-                .as_mut_slice(),
-        ) {
-            current_piece.position.y -= 1;
-        } else {
-            current_piece.placed = true;
-        }
+    if keys.just_pressed(KeyCode::RBracket) {
+        ghost.opacity = (ghost.opacity + GHOST_OPACITY_STEP).min(1.0);
     }
 }
 
@@ -975,34 +1011,54 @@ fn background(asset_server: Res<AssetServer>, mut handle: ResMut<BackgroundImage
     handle.0 = texture.clone();
 }
 
+/// The background picture, stretched to the height of the world.
+#[derive(Component)]
+struct Background {
+    /// Width over height of the picture.
+    aspect: f32,
+}
+
+fn fit_background(
+    layout: &BoardLayout,
+    background: &Background,
+    sprite: &mut Sprite,
+    transform: &mut Transform,
+) {
+    let world_size = layout.world_size();
+    sprite.custom_size = Some(Vec2::new(background.aspect * world_size.y, world_size.y));
+    transform.translation.x = world_size.x / 2.;
+}
+
 fn background_sprite_creator(
     mut commands: Commands,
     assets: Res<Assets<Image>>,
     bg_handle: Res<BackgroundImageHandle>,
+    layout: Res<BoardLayout>,
     mut ev_asset: EventReader<AssetEvent<Image>>,
 ) {
     for ev in ev_asset.iter() {
-        match ev {
-            AssetEvent::Created { handle } => {
-                if *handle == bg_handle.0 {
-                    let texture = assets.get(handle).unwrap();
-                    let ar = texture.size().x as f32 / texture.size().y as f32;
-                    commands.spawn(SpriteBundle {
-                        sprite: Sprite {
-                            custom_size: Some(Vec2::new(ar * WORLD_SIZE.y, WORLD_SIZE.y)),
-                            anchor: Anchor::BottomCenter,
-                            ..Default::default()
-                        },
+        if let AssetEvent::Created { handle } = ev {
+            if *handle == bg_handle.0 {
+                let texture = assets.get(handle).unwrap();
+                let background = Background {
+                    aspect: texture.size().x / texture.size().y,
+                };
+                let mut sprite = Sprite {
+                    anchor: Anchor::BottomCenter,
+                    ..Default::default()
+                };
+                let mut transform = Transform::default();
+                fit_background(&layout, &background, &mut sprite, &mut transform);
+                commands.spawn((
+                    SpriteBundle {
+                        sprite,
                         texture: bg_handle.0.clone(),
-                        transform: Transform {
-                            translation: Vec3::new(WORLD_SIZE.x / 2., 0.0, 0.0),
-                            ..Default::default()
-                        },
+                        transform,
                         ..Default::default()
-                    });
-                }
+                    },
+                    background,
+                ));
             }
-            _ => {}
         }
     }
 }
@@ -1017,49 +1073,225 @@ fn diagnostic_system(keys: Res<Input<KeyCode>>, mut diag_state: ResMut<FpsCounte
 }
 
 #[derive(Resource)]
-struct DisplayBoard(Board);
+struct BoardTiles(TileGrid);
 
+/// Most pieces the next queue can show.
+const MAX_PREVIEW: usize = 7;
+/// Rows between the tops of neighbouring pieces in the next queue.
+const NEXT_QUEUE_SPACING: usize = 3;
+
+/// One grid per slot in the next queue, soonest first.
 #[derive(Resource)]
-struct CurrentPieceBoard(Board);
+struct NextQueueBoard(Vec<TileGrid>);
+
+/// How many upcoming pieces to show, from 1 to `MAX_PREVIEW`.
+#[derive(Resource)]
+struct NextQueue {
+    count: usize,
+}
+
+impl Default for NextQueue {
+    fn default() -> Self {
+        Self { count: 5 }
+    }
+}
+
+/// `-` and `=` show fewer or more upcoming pieces.
+fn resize_next_queue(keys: Res<Input<KeyCode>>, mut next_queue: ResMut<NextQueue>) {
+    if keys.just_pressed(KeyCode::Minus) {
+        next_queue.count = next_queue.count.saturating_sub(1).max(1);
+    }
+    if keys.just_pressed(KeyCode::Equals) {
+        next_queue.count = (next_queue.count + 1).min(MAX_PREVIEW);
+    }
+}
+
+/// Preview of whatever is in the hold slot.
+#[derive(Resource)]
+struct HoldBoard(TileGrid);
+
+#[derive(Resource)]
+struct CurrentPieceBoard(TileGrid);
+
+#[derive(Resource)]
+struct GhostPieceBoard(TileGrid);
 
 #[derive(Component)]
 struct CurrentPieceTransform;
 
-pub struct HelloPlugin;
+#[derive(Component)]
+struct GhostPieceTransform;
+
+/// The order each frame runs in. Keys are read, the game ticks, whatever that set off gets dealt
+/// with, then everything is drawn from where the game ended up, all in the same frame.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameSet {
+    Input,
+    Simulation,
+    Resolve,
+    Render,
+}
+
+/// The game and everything that drives and draws it, without the window dressing. Enough to run
+/// headless in tests.
+pub struct GameplayPlugin {
+    /// Seed for the first game's pieces, a random one if `None`.
+    pub seed: Option<u64>,
+    /// Gravity table from the command line, used instead of each mode's own.
+    pub gravity: Option<GravityTable>,
+}
+
+impl Plugin for GameplayPlugin {
+    fn build(&self, app: &mut App) {
+        // Replaced with the chosen mode's game on the mode select screen.
+        app.insert_resource(Tetris(Game::new()))
+            .insert_resource(StartSeed(self.seed))
+            .insert_resource(CustomGravity(self.gravity.clone()))
+            .init_resource::<LatchedInput>()
+            .init_resource::<TickClock>()
+            .init_resource::<BoardLayout>()
+            .insert_resource(BoardTiles(TileGrid::new(0, 0)))
+            .insert_resource(NextQueueBoard(
+                (0..MAX_PREVIEW).map(|_| TileGrid::new(4, 4)).collect(),
+            ))
+            .init_resource::<NextQueue>()
+            .insert_resource(HoldBoard(TileGrid::new(4, 4)))
+            .insert_resource(CurrentPieceBoard(TileGrid::new(4, 4)))
+            .insert_resource(GhostPieceBoard(TileGrid::new(4, 4)))
+            .init_resource::<GhostPiece>()
+            .add_event::<GameEvent>()
+            .add_event::<RestartGame>()
+            .configure_sets(
+                (
+                    GameSet::Input,
+                    GameSet::Simulation,
+                    GameSet::Resolve,
+                    GameSet::Render,
+                )
+                    .chain(),
+            )
+            .add_plugin(ScreensPlugin)
+            .add_plugin(SprintPlugin)
+            .add_plugin(UltraPlugin)
+            .add_startup_system(setup_hud)
+            .add_systems(
+                (
+                    keyboard_input.in_set(OnUpdate(AppState::Playing)),
+                    toggle_ghost,
+                    resize_next_queue,
+                )
+                    .in_set(GameSet::Input),
+            )
+            .add_system(
+                tick_game
+                    .run_if(in_state(AppState::Playing))
+                    .in_set(GameSet::Simulation),
+            )
+            .add_systems((restart_game, announce_clears).in_set(GameSet::Resolve))
+            // Flushes between the steps so sprites show this frame's board, not last frame's.
+            .add_systems(
+                (
+                    build_board_view,
+                    apply_system_buffers,
+                    update_board_sprites.run_if(board_visible),
+                    update_piece_display_position.run_if(board_visible),
+                    apply_system_buffers,
+                    update_sprite_colors.run_if(board_visible),
+                )
+                    .chain()
+                    .in_set(GameSet::Render),
+            )
+            .add_systems(
+                (
+                    hide_board.run_if(board_hidden),
+                    render_score,
+                    render_timer,
+                    render_level,
+                    animate_announcements,
+                )
+                    .in_set(GameSet::Render),
+            );
+    }
+}
+
+pub struct HelloPlugin {
+    /// Seed for the first game's pieces, a random one if `None`.
+    pub seed: Option<u64>,
+    /// Gravity table from the command line, used instead of each mode's own.
+    pub gravity: Option<GravityTable>,
+}
 
 impl Plugin for HelloPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(Board::new(BOARD_SIZE.x as usize, BOARD_SIZE.y as usize))
-            .insert_resource(DisplayBoard(Board::new(4 as usize, 4 as usize)))
-            .insert_resource(CurrentPieceBoard(Board::new(4 as usize, 4 as usize)))
-            .insert_resource(Bag::new())
-            .insert_resource(Score(0))
-            .insert_resource(Level(0))
-            .insert_resource(BackgroundImageHandle(Handle::default()))
-            .add_startup_system(setup_cam)
-            .add_startup_system(setup_board)
-            .add_startup_system(sound_engine)
-            .add_startup_system(background)
-            .add_system(background_sprite_creator)
-            .add_system(render_score)
-            .add_system(render_level)
-            // .add_system(Board::update_sprite_visibility)
-            .add_system(Board::update_board_sprites)
-            // .add_system(Board::debug)
-            .add_system(Board::assess_board)
-            .add_system(place_piece)
-            .add_system(keyboard_input)
-            .add_system(update)
-            .add_system(update_piece_display_position)
-            .add_system(Board::update_sprite_colors)
-            .add_system(diagnostic_system);
+        app.add_plugin(GameplayPlugin {
+            seed: self.seed,
+            gravity: self.gravity.clone(),
+        })
+        .insert_resource(PersonalBests::load(RECORDS_FILE))
+        .insert_resource(BackgroundImageHandle(Handle::default()))
+        .add_startup_system(setup_cam)
+        .add_startup_system(sound_engine)
+        .add_startup_system(background)
+        .add_system(background_sprite_creator.in_set(GameSet::Render))
+        .add_system(diagnostic_system.in_set(GameSet::Input));
     }
 }
 
+/// What can be set from the command line.
+#[derive(Default)]
+struct Args {
+    /// `--seed <number>`, so a run someone shared can be played again.
+    seed: Option<u64>,
+    /// `--gravity <file>`, a table of `<level> <G>` lines to play with instead of the mode's.
+    gravity: Option<GravityTable>,
+    /// `--debug` turns on the cheats.
+    debug: bool,
+}
+
+impl Args {
+    fn from_env() -> Self {
+        let mut parsed = Args::default();
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => {
+                    parsed.seed = args.next().and_then(|seed| seed.parse().ok());
+                    if parsed.seed.is_none() {
+                        eprintln!("--seed wants a number, using a random one");
+                    }
+                }
+                "--gravity" => {
+                    parsed.gravity = args.next().and_then(|path| match load_gravity(&path) {
+                        Ok(table) => Some(table),
+                        Err(error) => {
+                            eprintln!("can't load gravity from {path}: {error}");
+                            None
+                        }
+                    });
+                }
+                "--debug" => parsed.debug = true,
+                _ => eprintln!("ignoring unknown argument {arg}"),
+            }
+        }
+        parsed
+    }
+}
+
+fn load_gravity(path: &str) -> Result<GravityTable, Box<dyn std::error::Error>> {
+    Ok(std::fs::read_to_string(path)?.parse()?)
+}
+
 fn main() {
-    App::new()
-        .add_plugins(DefaultPlugins)
+    let args = Args::from_env();
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins)
         .add_plugin(FpsCounterPlugin)
-        .add_plugin(HelloPlugin)
-        .run();
+        .add_plugin(HelloPlugin {
+            seed: args.seed,
+            gravity: args.gravity,
+        });
+    if args.debug {
+        app.add_plugin(DebugPlugin);
+    }
+    app.run();
 }
@@ -0,0 +1,526 @@
+//! Menus and overlays, and the state that decides which of them is up and whether the game runs.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use tetris_core::{Goal, GravityTable, Playfield, RandomizerKind, Ruleset, ScoringTable, Timing};
+
+use crate::sprint::PersonalBests;
+use crate::{format_time, GameSet, RestartGame, Tetris};
+
+const FONT: &str = "fonts/UbuntuMonoNerdFontCompleteMono.ttf";
+const COUNTDOWN_SECONDS: f32 = 3.0;
+/// Line goals Sprint can be played to.
+const SPRINT_GOALS: [usize; 3] = [20, 40, 100];
+/// Minutes Ultra can be played for.
+const ULTRA_MINUTES: [u64; 3] = [1, 2, 3];
+
+#[derive(States, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum AppState {
+    #[default]
+    Title,
+    ModeSelect,
+    /// 3-2-1 before the pieces start falling.
+    Countdown,
+    Playing,
+    Paused,
+    GameOver,
+}
+
+/// Run condition for everything that draws the board. It's hidden on the menus, and while paused
+/// so pausing can't be used to think.
+pub fn board_visible(state: Res<State<AppState>>) -> bool {
+    matches!(
+        state.0,
+        AppState::Countdown | AppState::Playing | AppState::GameOver
+    )
+}
+
+pub fn board_hidden(state: Res<State<AppState>>) -> bool {
+    !board_visible(state)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GameMode {
+    #[default]
+    Marathon,
+    Classic,
+    /// Clear this many lines as fast as possible.
+    Sprint(usize),
+    /// Score as much as possible in this many minutes.
+    Ultra(u64),
+}
+
+impl GameMode {
+    const ALL: [GameMode; 4] = [
+        GameMode::Marathon,
+        GameMode::Classic,
+        GameMode::Sprint(40),
+        GameMode::Ultra(2),
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            GameMode::Marathon => "Marathon",
+            GameMode::Classic => "Classic",
+            GameMode::Sprint(_) => "Sprint",
+            GameMode::Ultra(_) => "Ultra",
+        }
+    }
+
+    fn description(self) -> String {
+        match self {
+            GameMode::Marathon => "7-bag, guideline scoring".to_string(),
+            GameMode::Classic => "NES pieces and scoring on a 10x20 board".to_string(),
+            GameMode::Sprint(lines) => format!("< {lines} > lines as fast as you can"),
+            GameMode::Ultra(minutes) => format!("< {minutes} > minutes to score all you can"),
+        }
+    }
+
+    /// Whether `other` is the same mode, maybe with a different setting.
+    fn is(self, other: GameMode) -> bool {
+        std::mem::discriminant(&self) == std::mem::discriminant(&other)
+    }
+
+    /// The next setting along, `step` of them to the right. Modes without one stay as they are.
+    fn adjusted(self, step: isize) -> GameMode {
+        match self {
+            GameMode::Sprint(lines) => GameMode::Sprint(cycle(&SPRINT_GOALS, lines, step)),
+            GameMode::Ultra(minutes) => GameMode::Ultra(cycle(&ULTRA_MINUTES, minutes, step)),
+            mode => mode,
+        }
+    }
+
+    fn ruleset(self) -> Ruleset {
+        match self {
+            GameMode::Marathon => Ruleset::default(),
+            GameMode::Classic => Ruleset {
+                playfield: Playfield::guideline(),
+                gravity: GravityTable::nes(),
+                timing: Timing::nes(),
+                half_turn: None,
+                randomizer: RandomizerKind::Nes,
+                scoring: ScoringTable::Nes,
+                ..Default::default()
+            },
+            GameMode::Sprint(lines) => Ruleset {
+                playfield: Playfield::guideline(),
+                goal: Goal::Lines(lines),
+                ..Default::default()
+            },
+            GameMode::Ultra(minutes) => Ruleset {
+                playfield: Playfield::guideline(),
+                goal: Goal::Time(Duration::from_secs(minutes * 60)),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// The option `step` places along from `current`, wrapping round at either end.
+fn cycle<T: Copy + PartialEq>(options: &[T], current: T, step: isize) -> T {
+    let index = options.iter().position(|&option| option == current);
+    let index = (index.unwrap_or(0) as isize + step).rem_euclid(options.len() as isize);
+    options[index as usize]
+}
+
+#[derive(Resource, Default)]
+struct SelectedMode(GameMode);
+
+/// Seed from the command line, used up by the first game.
+#[derive(Resource)]
+pub struct StartSeed(pub Option<u64>);
+
+/// Gravity from the command line, played instead of the chosen mode's.
+#[derive(Resource)]
+pub struct CustomGravity(pub Option<GravityTable>);
+
+#[derive(Resource)]
+struct CountdownTimer(Timer);
+
+#[derive(Component)]
+struct TitleScreen;
+
+#[derive(Component)]
+struct ModeSelectScreen;
+
+#[derive(Component)]
+struct ModeOption(GameMode);
+
+#[derive(Component)]
+struct CountdownScreen;
+
+#[derive(Component)]
+struct CountdownText;
+
+#[derive(Component)]
+struct PauseScreen;
+
+#[derive(Component)]
+struct GameOverScreen;
+
+/// A full window column of centred text over a dark background.
+fn screen_node(background: Color) -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        background_color: background.into(),
+        ..Default::default()
+    }
+}
+
+fn screen_text(
+    font: &Handle<Font>,
+    text: impl Into<String>,
+    font_size: f32,
+    color: Color,
+) -> TextBundle {
+    TextBundle::from_section(
+        text,
+        TextStyle {
+            font: font.clone(),
+            font_size,
+            color,
+        },
+    )
+    .with_style(Style {
+        margin: UiRect::all(Val::Px(10.0)),
+        ..Default::default()
+    })
+}
+
+fn spawn_screen(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    marker: impl Component,
+    background: Color,
+    lines: Vec<(String, f32, Color)>,
+) {
+    let font = asset_server.load(FONT);
+    commands
+        .spawn((screen_node(background), marker))
+        .with_children(|parent| {
+            for (text, font_size, color) in lines {
+                parent.spawn(screen_text(&font, text, font_size, color));
+            }
+        });
+}
+
+fn despawn_screen<T: Component>(mut commands: Commands, screens: Query<Entity, With<T>>) {
+    for screen in screens.iter() {
+        commands.entity(screen).despawn_recursive();
+    }
+}
+
+fn show_title(mut commands: Commands, asset_server: Res<AssetServer>) {
+    spawn_screen(
+        &mut commands,
+        &asset_server,
+        TitleScreen,
+        Color::rgba(0.0, 0.0, 0.0, 0.85),
+        vec![
+            ("TETRIS".to_string(), 160.0, Color::WHITE),
+            ("Enter to start".to_string(), 50.0, Color::GRAY),
+        ],
+    );
+}
+
+fn title_input(keys: Res<Input<KeyCode>>, mut next_state: ResMut<NextState<AppState>>) {
+    if keys.any_just_pressed([KeyCode::Return, KeyCode::Space]) {
+        next_state.set(AppState::ModeSelect);
+    }
+}
+
+fn show_mode_select(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load(FONT);
+    commands
+        .spawn((
+            screen_node(Color::rgba(0.0, 0.0, 0.0, 0.85)),
+            ModeSelectScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn(screen_text(&font, "Choose a mode", 80.0, Color::WHITE));
+            for mode in GameMode::ALL {
+                parent.spawn((
+                    screen_text(&font, mode_label(mode), 50.0, Color::GRAY),
+                    ModeOption(mode),
+                ));
+            }
+            parent.spawn(screen_text(
+                &font,
+                "Up/Down to choose, Left/Right to change, Enter to play, Esc to go back",
+                30.0,
+                Color::GRAY,
+            ));
+        });
+}
+
+fn mode_select_input(
+    keys: Res<Input<KeyCode>>,
+    mut selected: ResMut<SelectedMode>,
+    mut start_seed: ResMut<StartSeed>,
+    custom_gravity: Res<CustomGravity>,
+    mut restarts: EventWriter<RestartGame>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let index = GameMode::ALL
+        .iter()
+        .position(|&mode| mode.is(selected.0))
+        .unwrap_or(0);
+    let count = GameMode::ALL.len();
+    if keys.any_just_pressed([KeyCode::Up, KeyCode::W]) {
+        selected.0 = GameMode::ALL[(index + count - 1) % count];
+    }
+    if keys.any_just_pressed([KeyCode::Down, KeyCode::S]) {
+        selected.0 = GameMode::ALL[(index + 1) % count];
+    }
+    if keys.any_just_pressed([KeyCode::Left, KeyCode::A]) {
+        selected.0 = selected.0.adjusted(-1);
+    }
+    if keys.any_just_pressed([KeyCode::Right, KeyCode::D]) {
+        selected.0 = selected.0.adjusted(1);
+    }
+    if keys.just_pressed(KeyCode::Escape) {
+        next_state.set(AppState::Title);
+    }
+    if keys.any_just_pressed([KeyCode::Return, KeyCode::Space]) {
+        let mut rules = selected.0.ruleset();
+        if let Some(gravity) = &custom_gravity.0 {
+            rules.gravity = gravity.clone();
+        }
+        restarts.send(RestartGame {
+            rules: Some(rules),
+            seed: start_seed.0.take(),
+        });
+    }
+}
+
+fn mode_label(mode: GameMode) -> String {
+    format!("{} - {}", mode.name(), mode.description())
+}
+
+fn highlight_selected_mode(
+    selected: Res<SelectedMode>,
+    mut options: Query<(&mut ModeOption, &mut Text)>,
+) {
+    for (mut option, mut text) in options.iter_mut() {
+        let section = &mut text.sections[0];
+        if option.0.is(selected.0) {
+            if option.0 != selected.0 {
+                option.0 = selected.0;
+                section.value = mode_label(selected.0);
+            }
+            section.style.color = Color::WHITE;
+        } else {
+            section.style.color = Color::GRAY;
+        }
+    }
+}
+
+fn start_countdown(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut timer: ResMut<CountdownTimer>,
+) {
+    timer.0.reset();
+    let font = asset_server.load(FONT);
+    commands
+        .spawn((screen_node(Color::NONE), CountdownScreen))
+        .with_children(|parent| {
+            parent.spawn((screen_text(&font, "", 200.0, Color::WHITE), CountdownText));
+        });
+}
+
+fn countdown(
+    time: Res<Time>,
+    mut timer: ResMut<CountdownTimer>,
+    mut texts: Query<&mut Text, With<CountdownText>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if timer.0.tick(time.delta()).finished() {
+        next_state.set(AppState::Playing);
+        return;
+    }
+    let remaining = timer.0.remaining_secs().ceil();
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = format!("{remaining}");
+    }
+}
+
+fn pause_input(keys: Res<Input<KeyCode>>, mut next_state: ResMut<NextState<AppState>>) {
+    if keys.just_pressed(KeyCode::P) {
+        next_state.set(AppState::Paused);
+    }
+}
+
+fn show_pause(mut commands: Commands, asset_server: Res<AssetServer>) {
+    spawn_screen(
+        &mut commands,
+        &asset_server,
+        PauseScreen,
+        Color::rgba(0.0, 0.0, 0.0, 0.7),
+        vec![
+            ("PAUSED".to_string(), 100.0, Color::WHITE),
+            ("P to carry on".to_string(), 40.0, Color::GRAY),
+            ("Esc to restart".to_string(), 40.0, Color::GRAY),
+            ("Enter to pick a mode".to_string(), 40.0, Color::GRAY),
+        ],
+    );
+}
+
+fn paused_input(
+    keys: Res<Input<KeyCode>>,
+    mut restarts: EventWriter<RestartGame>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if keys.just_pressed(KeyCode::P) {
+        next_state.set(AppState::Playing);
+    }
+    if keys.just_pressed(KeyCode::Escape) {
+        restarts.send(RestartGame::default());
+    }
+    if keys.just_pressed(KeyCode::Return) {
+        next_state.set(AppState::ModeSelect);
+    }
+}
+
+fn show_game_over(
+    mut commands: Commands,
+    tetris: Res<Tetris>,
+    bests: Res<PersonalBests>,
+    asset_server: Res<AssetServer>,
+) {
+    let game = &tetris.0;
+    let title = match game.rules().goal {
+        _ if !game.goal_reached() => "GAME OVER",
+        Goal::Time(_) => "TIME UP",
+        _ => "FINISHED",
+    };
+    let mut lines = vec![(title.to_string(), 100.0, Color::WHITE)];
+    let new_best = || ("New personal best!".to_string(), 60.0, Color::GOLD);
+    match (game.rules().goal, game.sprint()) {
+        (Goal::Lines(_), Some(run)) => {
+            if let Some(time) = run.time() {
+                lines.push((format!("Time: {}", format_time(time)), 60.0, Color::BEIGE));
+            }
+            if let Some(best) = bests
+                .records
+                .best_sprint(run.goal)
+                .and_then(|best| best.time())
+            {
+                lines.push((format!("Best: {}", format_time(best)), 60.0, Color::BEIGE));
+            }
+            if bests.new_best {
+                lines.push(new_best());
+            }
+            lines.push((
+                format!("Lines: {}/{}", game.lines(), run.goal),
+                60.0,
+                Color::BEIGE,
+            ));
+        }
+        (Goal::Time(limit), _) => {
+            lines.push((format!("Score: {}", game.score()), 60.0, Color::BEIGE));
+            if let Some(best) = bests.records.best_ultra(limit) {
+                lines.push((format!("Best: {best}"), 60.0, Color::BEIGE));
+            }
+            if bests.new_best {
+                lines.push(new_best());
+            }
+            lines.push((format!("Lines: {}", game.lines()), 60.0, Color::BEIGE));
+        }
+        _ => {
+            lines.push((format!("Score: {}", game.score()), 60.0, Color::BEIGE));
+            lines.push((format!("Level: {}", game.level()), 60.0, Color::BEIGE));
+            lines.push((format!("Lines: {}", game.lines()), 60.0, Color::BEIGE));
+        }
+    }
+    lines.push((format!("Seed: {}", game.seed()), 40.0, Color::GRAY));
+    lines.push((
+        "Esc to play again, Enter to pick a mode".to_string(),
+        40.0,
+        Color::GRAY,
+    ));
+    spawn_screen(
+        &mut commands,
+        &asset_server,
+        GameOverScreen,
+        Color::rgba(0.0, 0.0, 0.0, 0.7),
+        lines,
+    );
+}
+
+fn game_over_input(
+    keys: Res<Input<KeyCode>>,
+    mut restarts: EventWriter<RestartGame>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if keys.just_pressed(KeyCode::Escape) {
+        restarts.send(RestartGame::default());
+    }
+    if keys.just_pressed(KeyCode::Return) {
+        next_state.set(AppState::ModeSelect);
+    }
+}
+
+pub struct ScreensPlugin;
+
+impl Plugin for ScreensPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<AppState>()
+            .init_resource::<SelectedMode>()
+            .insert_resource(CountdownTimer(Timer::from_seconds(
+                COUNTDOWN_SECONDS,
+                TimerMode::Once,
+            )))
+            .add_system(show_title.in_schedule(OnEnter(AppState::Title)))
+            .add_system(
+                title_input
+                    .in_set(OnUpdate(AppState::Title))
+                    .in_set(GameSet::Input),
+            )
+            .add_system(despawn_screen::<TitleScreen>.in_schedule(OnExit(AppState::Title)))
+            .add_system(show_mode_select.in_schedule(OnEnter(AppState::ModeSelect)))
+            .add_systems(
+                (mode_select_input, highlight_selected_mode)
+                    .chain()
+                    .in_set(OnUpdate(AppState::ModeSelect))
+                    .in_set(GameSet::Input),
+            )
+            .add_system(
+                despawn_screen::<ModeSelectScreen>.in_schedule(OnExit(AppState::ModeSelect)),
+            )
+            .add_system(start_countdown.in_schedule(OnEnter(AppState::Countdown)))
+            .add_system(
+                countdown
+                    .in_set(OnUpdate(AppState::Countdown))
+                    .in_set(GameSet::Simulation),
+            )
+            .add_system(despawn_screen::<CountdownScreen>.in_schedule(OnExit(AppState::Countdown)))
+            .add_system(
+                pause_input
+                    .in_set(OnUpdate(AppState::Playing))
+                    .in_set(GameSet::Input),
+            )
+            .add_system(show_pause.in_schedule(OnEnter(AppState::Paused)))
+            .add_system(
+                paused_input
+                    .in_set(OnUpdate(AppState::Paused))
+                    .in_set(GameSet::Input),
+            )
+            .add_system(despawn_screen::<PauseScreen>.in_schedule(OnExit(AppState::Paused)))
+            .add_system(show_game_over.in_schedule(OnEnter(AppState::GameOver)))
+            .add_system(
+                game_over_input
+                    .in_set(OnUpdate(AppState::GameOver))
+                    .in_set(GameSet::Input),
+            )
+            .add_system(despawn_screen::<GameOverScreen>.in_schedule(OnExit(AppState::GameOver)));
+    }
+}
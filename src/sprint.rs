@@ -0,0 +1,127 @@
+//! Sprint: the splits of the run in progress, and personal bests to race against.
+
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use tetris_core::{GameEvent, Pace, Records};
+
+use crate::{restart_game, GameSet, PaceDisplay, RestartGame, Tetris};
+
+/// Where personal bests are kept, next to wherever the game is run from.
+pub const RECORDS_FILE: &str = "records.txt";
+
+/// Best runs so far, and the file they're saved to after each new one.
+#[derive(Resource, Default)]
+pub struct PersonalBests {
+    pub records: Records,
+    /// `None` keeps them in memory only.
+    file: Option<PathBuf>,
+    /// The last run finished was a new best, until the next one starts.
+    pub new_best: bool,
+}
+
+impl PersonalBests {
+    /// Reads them from `file`, starting afresh if there isn't one yet. A file that doesn't read
+    /// is left alone rather than written over.
+    pub fn load(file: impl Into<PathBuf>) -> Self {
+        let file = file.into();
+        let text = match std::fs::read_to_string(&file) {
+            Ok(text) => text,
+            Err(error) if error.kind() == ErrorKind::NotFound => String::new(),
+            Err(error) => {
+                warn!("Not keeping personal bests, {}: {error}", file.display());
+                return Self::default();
+            }
+        };
+        match text.parse() {
+            Ok(records) => Self {
+                records,
+                file: Some(file),
+                new_best: false,
+            },
+            Err(error) => {
+                warn!("Not keeping personal bests, {}: {error}", file.display());
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        let Some(file) = &self.file else {
+            return;
+        };
+        if let Err(error) = std::fs::write(file, self.records.to_string()) {
+            warn!(
+                "Couldn't save personal bests to {}: {error}",
+                file.display()
+            );
+        }
+    }
+}
+
+/// Records the run once the goal is reached. The game keeps the splits itself, tick by tick.
+fn track_sprint(
+    tetris: Res<Tetris>,
+    mut restarts: EventReader<RestartGame>,
+    mut game_events: EventReader<GameEvent>,
+    mut bests: ResMut<PersonalBests>,
+) {
+    if restarts.iter().last().is_some() {
+        game_events.clear();
+        return;
+    }
+    let Some(run) = tetris.0.sprint() else {
+        game_events.clear();
+        return;
+    };
+    for event in game_events.iter() {
+        if *event == GameEvent::GoalReached {
+            bests.new_best = bests.records.submit_sprint(run);
+            if bests.new_best {
+                bests.save();
+            }
+        }
+    }
+}
+
+fn format_pace(pace: Pace) -> (String, Color) {
+    let (sign, by, color) = match pace {
+        Pace::Ahead(by) => ('-', by, Color::GREEN),
+        Pace::Behind(by) => ('+', by, Color::RED),
+    };
+    let millis = by.as_millis();
+    (
+        format!("{sign}{}.{:03}", millis / 1000, millis % 1000),
+        color,
+    )
+}
+
+/// How far ahead or behind the best run this one is as of the last line.
+fn render_pace(
+    tetris: Res<Tetris>,
+    bests: Res<PersonalBests>,
+    mut paces: Query<&mut Text, With<PaceDisplay>>,
+) {
+    let pace = tetris.0.sprint().and_then(|run| {
+        let best = bests.records.best_sprint(run.goal)?;
+        run.pace(best)
+    });
+    for mut text in paces.iter_mut() {
+        let section = &mut text.sections[0];
+        match pace {
+            Some(pace) => (section.value, section.style.color) = format_pace(pace),
+            None => section.value.clear(),
+        }
+    }
+}
+
+pub struct SprintPlugin;
+
+impl Plugin for SprintPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PersonalBests>()
+            .add_system(track_sprint.after(restart_game).in_set(GameSet::Resolve))
+            .add_system(render_pace.in_set(GameSet::Render));
+    }
+}
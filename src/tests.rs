@@ -0,0 +1,177 @@
+//! Runs whole frames of the app without a window, to check the systems happen in the right order.
+
+use std::time::{Duration, Instant};
+
+use bevy::{input::InputPlugin, prelude::*};
+use tetris_core::{Game, Goal, Input as GameInput, Piece, Ruleset, TICK};
+
+use crate::screens::AppState;
+use crate::sprint::PersonalBests;
+use crate::{
+    Announcement, BoardTiles, GameplayPlugin, LatchedInput, RestartGame, ScoreDisplay, Tetris,
+    MAX_TICKS_PER_FRAME,
+};
+
+/// Seed for the games the tests play, so every run gets the same pieces.
+const SEED: u64 = 4;
+
+/// A game on the default rules with whatever `tweak` changes.
+fn game_with(tweak: impl FnOnce(&mut Ruleset)) -> Game {
+    let mut rules = Ruleset::default();
+    tweak(&mut rules);
+    Game::seeded(rules, SEED)
+}
+
+/// The gameplay schedule on its own, with the clock under the test's control.
+fn headless_app() -> App {
+    let mut app = App::new();
+    app.add_plugin(TaskPoolPlugin::default())
+        .add_plugin(TypeRegistrationPlugin)
+        .add_plugin(AssetPlugin::default())
+        .add_plugin(InputPlugin)
+        .init_resource::<Time>()
+        .add_plugin(GameplayPlugin {
+            seed: Some(1),
+            gravity: None,
+        });
+    app
+}
+
+/// Gets to the point of playing `game`, with the bottom row full apart from where the current
+/// piece will land. Returns when the clock started.
+fn playing_with_single_set_up(app: &mut App, game: Game) -> Instant {
+    let start = Instant::now();
+    app.world.resource_mut::<Time>().update_with_instant(start);
+    app.update();
+
+    let mut tetris = app.world.resource_mut::<Tetris>();
+    tetris.0 = game;
+    let landing = tetris.0.ghost_piece().cells();
+    for col in 0..tetris.0.board().width() {
+        if !landing.contains(&IVec2::new(col as i32, 0)) {
+            tetris.0.board_mut().set(col, 0, Some(Piece::O));
+        }
+    }
+    app.world
+        .resource_mut::<NextState<AppState>>()
+        .set(AppState::Playing);
+    app.update();
+    start
+}
+
+/// Hard drops on the next frame, which is one tick long.
+fn drop_next_frame(app: &mut App, start: Instant) {
+    app.world.resource_mut::<LatchedInput>().0 = GameInput {
+        hard_drop: true,
+        ..Default::default()
+    };
+    app.world
+        .resource_mut::<Time>()
+        .update_with_instant(start + TICK);
+    app.update();
+}
+
+#[test]
+fn a_clear_is_drawn_on_the_frame_it_happens() {
+    let mut app = headless_app();
+    let start = playing_with_single_set_up(&mut app, Game::seeded(Ruleset::default(), SEED));
+    drop_next_frame(&mut app, start);
+
+    let game = &app.world.resource::<Tetris>().0;
+    assert_eq!(game.lines(), 1);
+    let score = game.score();
+    let board = game.board().clone();
+
+    let tiles = &app.world.resource::<BoardTiles>().0;
+    for row in 0..tiles.height {
+        for col in 0..board.width() {
+            let visibility = app.world.get::<Visibility>(tiles.squares[row][col]);
+            let shown = visibility == Some(&Visibility::Visible);
+            assert_eq!(shown, board.get(col, row).is_some(), "tile {col}, {row}");
+        }
+    }
+
+    let mut scores = app.world.query_filtered::<&Text, With<ScoreDisplay>>();
+    let text = scores.single(&app.world);
+    assert_eq!(text.sections[0].value, score.to_string());
+
+    let mut announcements = app.world.query::<&Announcement>();
+    assert_eq!(announcements.iter(&app.world).count(), 1);
+}
+
+#[test]
+fn rows_flash_on_the_frame_they_fill() {
+    let mut app = headless_app();
+    let game = game_with(|rules| rules.timing.line_clear = Duration::from_millis(200));
+    let start = playing_with_single_set_up(&mut app, game);
+    drop_next_frame(&mut app, start);
+
+    let width = app.world.resource::<Tetris>().0.board().width();
+    let tiles = &app.world.resource::<BoardTiles>().0;
+    let bottom_row: Vec<Entity> = tiles.squares[0][..width].to_vec();
+    for tile in bottom_row {
+        let sprite = app.world.get::<Sprite>(tile).unwrap();
+        assert_eq!(sprite.color, Color::WHITE);
+    }
+}
+
+#[test]
+fn a_sprint_is_recorded_on_the_frame_it_finishes() {
+    let mut app = headless_app();
+    let game = game_with(|rules| rules.goal = Goal::Lines(1));
+    let start = playing_with_single_set_up(&mut app, game);
+    drop_next_frame(&mut app, start);
+
+    let bests = app.world.resource::<PersonalBests>();
+    assert!(bests.new_best);
+    assert_eq!(bests.records.best_sprint(1).unwrap().time(), Some(TICK));
+    assert_eq!(
+        app.world.resource::<NextState<AppState>>().0,
+        Some(AppState::GameOver)
+    );
+}
+
+#[test]
+fn an_ultra_score_is_recorded_when_time_runs_out() {
+    let mut app = headless_app();
+    let game = game_with(|rules| rules.goal = Goal::Time(TICK));
+    let start = playing_with_single_set_up(&mut app, game);
+    drop_next_frame(&mut app, start);
+
+    let game = &app.world.resource::<Tetris>().0;
+    assert!(game.goal_reached());
+    let bests = app.world.resource::<PersonalBests>();
+    assert!(bests.new_best);
+    assert_eq!(bests.records.best_ultra(TICK), Some(game.score()));
+}
+
+#[test]
+fn restarting_forgets_the_last_run_was_a_best() {
+    let mut app = headless_app();
+    let game = game_with(|rules| rules.goal = Goal::Time(TICK));
+    let start = playing_with_single_set_up(&mut app, game);
+    drop_next_frame(&mut app, start);
+    assert!(app.world.resource::<PersonalBests>().new_best);
+
+    app.world.send_event(RestartGame {
+        rules: None,
+        seed: Some(1),
+    });
+    app.update();
+    assert!(!app.world.resource::<PersonalBests>().new_best);
+}
+
+#[test]
+fn a_long_stall_only_catches_up_a_few_ticks() {
+    let mut app = headless_app();
+    let game = game_with(|rules| rules.goal = Goal::Time(Duration::from_secs(60)));
+    let start = playing_with_single_set_up(&mut app, game);
+    app.world
+        .resource_mut::<Time>()
+        .update_with_instant(start + Duration::from_secs(10));
+    app.update();
+    assert_eq!(
+        app.world.resource::<Tetris>().0.elapsed(),
+        TICK * MAX_TICKS_PER_FRAME
+    );
+}
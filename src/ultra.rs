@@ -0,0 +1,39 @@
+//! Ultra: the score when time runs out goes into the personal bests.
+
+use bevy::prelude::*;
+use tetris_core::{GameEvent, Goal};
+
+use crate::sprint::PersonalBests;
+use crate::{restart_game, GameSet, RestartGame, Tetris};
+
+fn record_ultra(
+    tetris: Res<Tetris>,
+    mut restarts: EventReader<RestartGame>,
+    mut game_events: EventReader<GameEvent>,
+    mut bests: ResMut<PersonalBests>,
+) {
+    if restarts.iter().last().is_some() {
+        game_events.clear();
+        return;
+    }
+    let Goal::Time(limit) = tetris.0.rules().goal else {
+        game_events.clear();
+        return;
+    };
+    for event in game_events.iter() {
+        if *event == GameEvent::GoalReached {
+            bests.new_best = bests.records.submit_ultra(limit, tetris.0.score());
+            if bests.new_best {
+                bests.save();
+            }
+        }
+    }
+}
+
+pub struct UltraPlugin;
+
+impl Plugin for UltraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(record_ultra.after(restart_game).in_set(GameSet::Resolve));
+    }
+}
@@ -0,0 +1,65 @@
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+use crate::piece::Piece;
+use crate::randomizer::{peek_by_dealing, Randomizer};
+
+/// Deals out `copies` of each of the seven pieces in a random order before any of them come round
+/// again, one copy is the usual 7-bag and two is a 14-bag. The order only depends on the seed,
+/// ChaCha gives the same numbers on every platform and version so a seed can be shared.
+#[derive(Debug, Clone)]
+pub struct Bag {
+    pieces: Vec<Piece>,
+    copies: usize,
+    rng: ChaCha8Rng,
+}
+
+impl Bag {
+    pub fn new(seed: u64) -> Self {
+        Self::with_copies(seed, 1)
+    }
+
+    pub fn with_copies(seed: u64, copies: usize) -> Self {
+        let mut bag = Self {
+            pieces: vec![],
+            copies: copies.max(1),
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        };
+        bag.fill_bag();
+        bag
+    }
+
+    /// Fisher–Yates, every order is equally likely.
+    fn fill_bag(&mut self) {
+        let mut pieces = Piece::ALL.repeat(self.copies);
+        for i in (1..pieces.len()).rev() {
+            let j = self.rng.gen_range(0..=i);
+            pieces.swap(i, j);
+        }
+        self.pieces = pieces;
+    }
+}
+
+impl Randomizer for Bag {
+    fn next_piece(&mut self) -> Piece {
+        let ret = self.pieces.pop().unwrap();
+        if self.pieces.is_empty() {
+            self.fill_bag();
+        }
+        ret
+    }
+
+    fn peek(&self, count: usize) -> Vec<Piece> {
+        peek_by_dealing(self, count)
+    }
+
+    fn box_clone(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+impl Default for Bag {
+    fn default() -> Self {
+        Self::new(rand::random())
+    }
+}
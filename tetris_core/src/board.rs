@@ -0,0 +1,136 @@
+use glam::IVec2;
+
+use crate::piece::{get_piece_meat_positions, Piece};
+
+/// The playfield. Row 0 is the bottom of the board, each cell remembers which piece filled it so
+/// whoever draws it can pick a colour. Above the visible rows is the buffer zone, which works like
+/// the rest of the board but mostly isn't drawn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board {
+    width: usize,
+    height: usize,
+    /// Rows stored above `height`, nothing fits above those.
+    buffer_rows: usize,
+    cells: Vec<Vec<Option<Piece>>>,
+}
+
+impl Board {
+    /// A board with as many rows above the top as there are on it.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::with_buffer(width, height, height)
+    }
+
+    pub fn with_buffer(width: usize, height: usize, buffer_rows: usize) -> Self {
+        Self {
+            width,
+            height,
+            buffer_rows,
+            cells: vec![vec![None; width]; height + buffer_rows],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Visible rows, not counting the buffer zone.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Every row, buffer zone included.
+    pub fn total_height(&self) -> usize {
+        self.height + self.buffer_rows
+    }
+
+    pub fn buffer_rows(&self) -> usize {
+        self.buffer_rows
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<Piece> {
+        self.cells[y][x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, cell: Option<Piece>) {
+        self.cells[y][x] = cell;
+    }
+
+    pub fn is_filled(&self, x: usize, y: usize) -> bool {
+        self.cells[y][x].is_some()
+    }
+
+    pub fn clear(&mut self) {
+        for row in self.cells.iter_mut() {
+            row.fill(None);
+        }
+    }
+
+    /// Nothing left on the board at all.
+    pub fn is_empty(&self) -> bool {
+        self.cells.iter().flatten().all(Option::is_none)
+    }
+
+    /// Whether a piece can't go in `cell`. The walls, the floor and anything past the buffer zone
+    /// are blocked, as is any filled cell.
+    pub fn is_blocked(&self, cell: IVec2) -> bool {
+        if cell.x < 0
+            || cell.x >= self.width as i32
+            || cell.y < 0
+            || cell.y >= self.total_height() as i32
+        {
+            return true;
+        }
+        self.cells[cell.y as usize][cell.x as usize].is_some()
+    }
+
+    pub fn check_piece_obstructed(&self, piece: Piece, offset: IVec2, rotation: i32) -> bool {
+        get_piece_meat_positions(piece, offset, rotation)
+            .into_iter()
+            .any(|cell| self.is_blocked(cell))
+    }
+
+    /// Where the piece would come to rest if it dropped straight down from `offset`.
+    pub fn landing_position(&self, piece: Piece, offset: IVec2, rotation: i32) -> IVec2 {
+        let mut position = offset;
+        while !self.check_piece_obstructed(piece, position + IVec2::NEG_Y, rotation) {
+            position.y -= 1;
+        }
+        position
+    }
+
+    /// Writes the piece into the board. Cells above the buffer zone have nowhere to go and are
+    /// dropped.
+    pub fn place_piece(&mut self, piece: Piece, offset: IVec2, rotation: i32) {
+        let meat = get_piece_meat_positions(piece, offset, rotation);
+        let total_height = self.total_height() as i32;
+        for pos in meat.iter().filter(|pos| pos.y < total_height) {
+            self.cells[pos.y as usize][pos.x as usize] = Some(piece);
+        }
+    }
+
+    /// Shoves everything up a row and fills the bottom row with `piece`, apart from column `hole`.
+    /// Whatever was in the top row of the buffer zone is lost.
+    pub fn push_garbage_row(&mut self, hole: usize, piece: Piece) {
+        let mut row = vec![Some(piece); self.width];
+        row[hole.min(self.width - 1)] = None;
+        self.cells.pop();
+        self.cells.insert(0, row);
+    }
+
+    /// Removes every full row, drops everything above it and returns how many rows went.
+    pub fn clear_full_rows(&mut self) -> usize {
+        let mut rows_to_remove = vec![];
+        for row in 0..self.total_height() {
+            let full = self.cells[row].iter().all(|x| x.is_some());
+            if full {
+                rows_to_remove.push(row);
+            }
+        }
+
+        for row in rows_to_remove.iter().rev() {
+            self.cells.remove(*row);
+            self.cells.push(vec![None; self.width]);
+        }
+        rows_to_remove.len()
+    }
+}
@@ -0,0 +1,847 @@
+use std::time::Duration;
+
+use glam::IVec2;
+
+use crate::board::Board;
+use crate::gravity::MAX_GRAVITY;
+use crate::piece::{CurrentPiece, Piece, PieceState};
+use crate::randomizer::Randomizer;
+use crate::records::SprintRun;
+use crate::rules::{Goal, LockReset, Ruleset, SoftDrop, TopOutReason};
+use crate::scoring::{self, ClearAction, TSpin};
+use crate::srs::{self, Rotation};
+
+pub const MAX_LEVEL: usize = 29;
+/// One 60 Hz frame, what [`Game::tick`] advances by. Rounded up so 60 of them make a whole second.
+pub const TICK: Duration = Duration::from_nanos(16_666_667);
+
+/// What the player is doing this step. `soft_drop` and the `_held` fields are whether the key is
+/// down, everything else is a "just pressed" edge.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Input {
+    pub rotate_cw: bool,
+    pub rotate_cw_held: bool,
+    pub rotate_ccw: bool,
+    pub rotate_ccw_held: bool,
+    /// Turn 180, if the ruleset allows it.
+    pub rotate_180: bool,
+    pub left: bool,
+    pub left_held: bool,
+    pub right: bool,
+    pub right_held: bool,
+    pub soft_drop: bool,
+    pub hard_drop: bool,
+    pub hold: bool,
+    pub hold_held: bool,
+}
+
+impl Input {
+    /// Folds a newer sample into this one, for when several come in before the game steps. A
+    /// press in either counts, held keys are whatever `newer` says.
+    pub fn latch(self, newer: Input) -> Input {
+        Input {
+            rotate_cw: self.rotate_cw || newer.rotate_cw,
+            rotate_ccw: self.rotate_ccw || newer.rotate_ccw,
+            rotate_180: self.rotate_180 || newer.rotate_180,
+            left: self.left || newer.left,
+            right: self.right || newer.right,
+            hard_drop: self.hard_drop || newer.hard_drop,
+            hold: self.hold || newer.hold,
+            ..newer
+        }
+    }
+
+    /// Just the keys being held, with the presses used up.
+    pub fn held(self) -> Input {
+        Input {
+            rotate_cw: false,
+            rotate_ccw: false,
+            rotate_180: false,
+            left: false,
+            right: false,
+            hard_drop: false,
+            hold: false,
+            ..self
+        }
+    }
+}
+
+/// Things that happened during a step, for whoever is drawing or listening.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    PieceSpawned(Piece),
+    PieceLocked(Piece),
+    /// The current piece went into the hold slot.
+    Held(Piece),
+    /// A lock that scored for clearing lines, a T-spin or both.
+    Cleared {
+        action: ClearAction,
+        points: usize,
+    },
+    LevelUp(usize),
+    GameOver(TopOutReason),
+    /// The ruleset's goal was reached, which ends the game without a top out.
+    GoalReached,
+}
+
+/// A pause after a lock while the game waits for the next piece.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delay {
+    /// Full rows are still on the board, they vanish when this runs out.
+    LineClear(Duration),
+    /// Entry delay (ARE), the next piece spawns when this runs out.
+    Entry(Duration),
+}
+
+#[derive(Debug, Clone)]
+pub struct Game {
+    rules: Ruleset,
+    seed: u64,
+    board: Board,
+    randomizer: Box<dyn Randomizer>,
+    current_piece: CurrentPiece,
+    held_piece: Option<Piece>,
+    hold_used: bool,
+    score: usize,
+    level: usize,
+    lines: usize,
+    cleared_lines: usize,
+    /// Line clears in a row so far, `None` once a piece locks without clearing anything.
+    combo: Option<usize>,
+    /// The last line clear was a difficult one.
+    back_to_back: bool,
+    gravity_elapsed: Duration,
+    auto_shift: AutoShift,
+    /// Set between a lock and the next spawn, the locked piece stays as the current one until then.
+    delay: Option<Delay>,
+    /// Presses that couldn't do anything yet, tried again until they work or go stale.
+    buffered: Buffered,
+    top_out: Option<TopOutReason>,
+    goal_reached: bool,
+    /// Time played, it stops once the game is over.
+    elapsed: Duration,
+    /// Splits of the run, for a game with a line goal.
+    sprint: Option<SprintRun>,
+}
+
+/// Rotate and hold presses waiting for a piece they can work on. Ones made during a delay wait for
+/// the next piece, the rest last as long as the ruleset's input buffer.
+#[derive(Debug, Clone, Copy, Default)]
+struct Buffered {
+    rotation: Option<Rotation>,
+    hold: bool,
+    /// How long the oldest of them has been waiting while a piece was in play.
+    age: Duration,
+}
+
+/// Which way auto shift is pushing and how long the key has been down for.
+#[derive(Debug, Clone, Copy, Default)]
+struct AutoShift {
+    direction: Option<IVec2>,
+    charge: Duration,
+    /// What is left of the DAS cut delay.
+    cut: Duration,
+}
+
+impl Game {
+    pub fn new() -> Self {
+        Self::with_rules(Ruleset::default())
+    }
+
+    pub fn with_rules(rules: Ruleset) -> Self {
+        Self::seeded(rules, rand::random())
+    }
+
+    /// A game whose pieces come out in the same order every time for the same `seed`.
+    pub fn seeded(rules: Ruleset, seed: u64) -> Self {
+        let playfield = rules.playfield;
+        let board = Board::with_buffer(playfield.width, playfield.height, playfield.buffer_rows);
+        let mut randomizer = rules.randomizer.build(seed);
+        let current_piece = CurrentPiece::new(randomizer.next_piece(), &playfield);
+        Self {
+            seed,
+            board,
+            randomizer,
+            current_piece,
+            held_piece: None,
+            hold_used: false,
+            score: 0,
+            level: 0,
+            lines: 0,
+            cleared_lines: 0,
+            combo: None,
+            back_to_back: false,
+            gravity_elapsed: Duration::ZERO,
+            auto_shift: AutoShift::default(),
+            delay: None,
+            buffered: Buffered::default(),
+            top_out: None,
+            goal_reached: false,
+            elapsed: Duration::ZERO,
+            sprint: match rules.goal {
+                Goal::Lines(goal) => Some(SprintRun::new(goal)),
+                Goal::Endless | Goal::Time(_) => None,
+            },
+            rules,
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn board_mut(&mut self) -> &mut Board {
+        &mut self.board
+    }
+
+    pub fn current_piece(&self) -> &CurrentPiece {
+        &self.current_piece
+    }
+
+    /// The current piece moved down to where a hard drop would put it.
+    pub fn ghost_piece(&self) -> CurrentPiece {
+        let mut ghost = self.current_piece;
+        ghost.position = self
+            .board
+            .landing_position(ghost.piece, ghost.position, ghost.rotation);
+        ghost
+    }
+
+    pub fn next_piece(&self) -> Piece {
+        self.randomizer.peek(1)[0]
+    }
+
+    /// The next `count` pieces in the order they will spawn.
+    pub fn next_pieces(&self, count: usize) -> Vec<Piece> {
+        self.randomizer.peek(count)
+    }
+
+    pub fn held_piece(&self) -> Option<Piece> {
+        self.held_piece
+    }
+
+    /// Whether holding is allowed right now, it is used up until the current piece locks.
+    pub fn can_hold(&self) -> bool {
+        !self.hold_used
+    }
+
+    pub fn score(&self) -> usize {
+        self.score
+    }
+
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    /// Total lines cleared this game.
+    pub fn lines(&self) -> usize {
+        self.lines
+    }
+
+    pub fn rules(&self) -> &Ruleset {
+        &self.rules
+    }
+
+    /// What the piece order was seeded with, pass it to [`Game::seeded`] to play the same pieces.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// How the game ended, `None` while it is still going.
+    pub fn top_out(&self) -> Option<TopOutReason> {
+        self.top_out
+    }
+
+    /// Whether the game ended by reaching the ruleset's goal rather than topping out.
+    pub fn goal_reached(&self) -> bool {
+        self.goal_reached
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.top_out.is_some() || self.goal_reached
+    }
+
+    /// Time played so far, counting every step since the start. It stops when the game ends.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// When each line was cleared, `None` unless the ruleset has a line goal.
+    pub fn sprint(&self) -> Option<&SprintRun> {
+        self.sprint.as_ref()
+    }
+
+    /// Starts over with the same rules and a new `seed`. Everything else goes back to exactly how
+    /// [`Game::seeded`] leaves it, since that's what builds it.
+    pub fn restart(&mut self, seed: u64) {
+        *self = Self::seeded(self.rules.clone(), seed);
+    }
+
+    /// The line clear or entry delay being waited through, `None` while a piece is in play.
+    pub fn delay(&self) -> Option<Delay> {
+        self.delay
+    }
+
+    /// Advances the game by one [`TICK`]. The same seed, rules and ticks of input always play out
+    /// the same way.
+    pub fn tick(&mut self, input: Input) -> Vec<GameEvent> {
+        self.step(input, TICK)
+    }
+
+    /// Advances the game by `dt`, applying `input` first. Does nothing once the game is over.
+    /// Everything happens in the same order every time: presses, auto shift, gravity, the lock
+    /// delay, then locking, clearing and the next spawn.
+    pub fn step(&mut self, input: Input, dt: Duration) -> Vec<GameEvent> {
+        let mut events = vec![];
+        if self.is_over() {
+            return events;
+        }
+        self.elapsed += dt;
+        if let Goal::Time(limit) = self.rules.goal {
+            if self.elapsed >= limit {
+                self.elapsed = limit;
+                self.reach_goal(&mut events);
+                return events;
+            }
+        }
+        if self.delay.is_some() {
+            self.wait(input, dt, &mut events);
+            return events;
+        }
+        self.age_buffer(dt);
+        self.handle_input(input, &mut events);
+        self.apply_auto_shift(input, dt);
+        self.apply_gravity(input.soft_drop, dt);
+        self.update_lock_delay(dt);
+        if self.current_piece.state == PieceState::Locked {
+            self.place_piece(input, &mut events);
+        }
+        events
+    }
+
+    /// Counts down the current delay. Nothing moves, but DAS keeps charging and rotate and hold
+    /// presses are kept for the next piece.
+    fn wait(&mut self, input: Input, dt: Duration, events: &mut Vec<GameEvent>) {
+        if input.rotate_ccw {
+            self.buffer_rotation(Rotation::CounterClockwise);
+        }
+        if input.rotate_cw {
+            self.buffer_rotation(Rotation::Clockwise);
+        }
+        if input.rotate_180 && self.rules.half_turn.is_some() {
+            self.buffer_rotation(Rotation::Half);
+        }
+        if input.hold {
+            self.buffer_hold();
+        }
+        self.apply_auto_shift(input, dt);
+        match self.delay {
+            Some(Delay::LineClear(remaining)) if remaining > dt => {
+                self.delay = Some(Delay::LineClear(remaining - dt));
+            }
+            Some(Delay::LineClear(_)) => {
+                self.board.clear_full_rows();
+                self.start_entry(self.rules.timing.line_are, input, events);
+            }
+            Some(Delay::Entry(remaining)) if remaining > dt => {
+                self.delay = Some(Delay::Entry(remaining - dt));
+            }
+            Some(Delay::Entry(_)) => self.spawn_next(input, events),
+            None => {}
+        }
+    }
+
+    /// Waits `are` before the next piece, or brings it in straight away if there's no wait.
+    fn start_entry(&mut self, are: Duration, input: Input, events: &mut Vec<GameEvent>) {
+        if are.is_zero() {
+            self.spawn_next(input, events);
+        } else {
+            self.delay = Some(Delay::Entry(are));
+        }
+    }
+
+    fn buffer_rotation(&mut self, rotation: Rotation) {
+        self.buffered.rotation = Some(rotation);
+        self.buffered.age = Duration::ZERO;
+    }
+
+    fn buffer_hold(&mut self) {
+        self.buffered.hold = true;
+        self.buffered.age = Duration::ZERO;
+    }
+
+    /// Forgets buffered presses once they're older than the input buffer.
+    fn age_buffer(&mut self, dt: Duration) {
+        self.buffered.age += dt;
+        if self.buffered.age > self.rules.handling.input_buffer {
+            self.buffered = Buffered::default();
+        }
+    }
+
+    fn handle_input(&mut self, input: Input, events: &mut Vec<GameEvent>) {
+        // A press that didn't work before gets another go, unless there's a fresh one.
+        let buffered = std::mem::take(&mut self.buffered);
+        let pressed = if input.rotate_cw {
+            Some(Rotation::Clockwise)
+        } else if input.rotate_ccw {
+            Some(Rotation::CounterClockwise)
+        } else if input.rotate_180 && self.rules.half_turn.is_some() {
+            Some(Rotation::Half)
+        } else {
+            None
+        };
+        // Both at once turns one way and then back, like it always has.
+        if input.rotate_ccw && input.rotate_cw {
+            self.rotate(Rotation::CounterClockwise);
+        }
+        if let Some(rotation) = pressed.or(buffered.rotation) {
+            if !self.rotate(rotation) {
+                self.buffered.rotation = Some(rotation);
+                self.buffered.age = if pressed.is_some() {
+                    Duration::ZERO
+                } else {
+                    buffered.age
+                };
+            }
+        }
+        // Holds are once per piece, a second go at it is just ignored.
+        if input.hold && self.can_hold() {
+            self.hold(None, events);
+            self.check_block_out(events);
+        }
+        if input.left {
+            self.shift(IVec2::NEG_X);
+        }
+        if input.right {
+            self.shift(IVec2::X);
+        }
+        if input.hard_drop {
+            let ghost = self.ghost_piece();
+            let rows = (self.current_piece.position.y - ghost.position.y) as usize;
+            self.score += self.rules.scoring.hard_drop_points(rows);
+            self.current_piece = CurrentPiece {
+                state: PieceState::Locked,
+                // Dropping after a spin means it wasn't the spin that put it there.
+                last_kick: ghost.last_kick.filter(|_| rows == 0),
+                ..ghost
+            };
+        }
+    }
+
+    /// Where the current piece would end up turned `direction`, and the kick it took. A 180 only
+    /// works if the ruleset has kicks for it.
+    fn turned(&self, direction: Rotation) -> Option<(CurrentPiece, usize)> {
+        let half_turn = match (direction, self.rules.half_turn) {
+            (Rotation::Half, None) => return None,
+            (_, half_turn) => half_turn.unwrap_or_default(),
+        };
+        srs::rotate_with(&self.board, &self.current_piece, direction, half_turn)
+    }
+
+    /// Turns the current piece with SRS kicks, leaving it where it was if nothing fits. A piece
+    /// that's already locked stays put.
+    fn rotate(&mut self, direction: Rotation) -> bool {
+        if self.current_piece.state == PieceState::Locked {
+            return false;
+        }
+        match self.turned(direction) {
+            Some((rotated, kick)) => {
+                // A 180 still counts as a spin, but none of its kicks is the big one that makes a
+                // mini T-spin a full one.
+                let kick = if direction == Rotation::Half { 0 } else { kick };
+                self.current_piece = CurrentPiece {
+                    last_kick: Some(kick),
+                    ..rotated
+                };
+                self.auto_shift.cut = self.rules.handling.das_cut_delay;
+                self.reset_lock_delay_on_move();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn shift(&mut self, offset: IVec2) -> bool {
+        let current_piece = &mut self.current_piece;
+        if self.delay.is_some()
+            || current_piece.state == PieceState::Locked
+            || self.board.check_piece_obstructed(
+                current_piece.piece,
+                current_piece.position + offset,
+                current_piece.rotation,
+            )
+        {
+            return false;
+        }
+        current_piece.position += offset;
+        current_piece.last_kick = None;
+        self.reset_lock_delay_on_move();
+        true
+    }
+
+    /// Works out which way auto shift is going from the held keys, the most recently pressed one
+    /// wins, then charges DAS and moves once for every ARR tick that falls inside this step.
+    fn apply_auto_shift(&mut self, input: Input, dt: Duration) {
+        let direction = if input.left {
+            Some(IVec2::NEG_X)
+        } else if input.right {
+            Some(IVec2::X)
+        } else {
+            match self.auto_shift.direction {
+                Some(IVec2::NEG_X) if input.left_held => Some(IVec2::NEG_X),
+                Some(IVec2::X) if input.right_held => Some(IVec2::X),
+                _ if input.left_held => Some(IVec2::NEG_X),
+                _ if input.right_held => Some(IVec2::X),
+                _ => None,
+            }
+        };
+        if direction != self.auto_shift.direction || input.left || input.right {
+            // A press already moved the piece in `handle_input`, letting go of one key while the
+            // other is still down moves it the other way here. Either way charging starts over.
+            if let (false, false, Some(direction)) = (input.left, input.right, direction) {
+                self.shift(direction);
+            }
+            self.auto_shift.direction = direction;
+            self.auto_shift.charge = Duration::ZERO;
+            return;
+        }
+        let Some(direction) = direction else {
+            return;
+        };
+
+        let handling = self.rules.handling;
+        let cut = self.auto_shift.cut;
+        self.auto_shift.cut = cut.saturating_sub(dt);
+        let dt = dt.saturating_sub(cut);
+        let before = self.auto_shift.charge;
+        self.auto_shift.charge += dt;
+        if self.auto_shift.charge < handling.das {
+            return;
+        }
+        if handling.arr.is_zero() {
+            while self.shift(direction) {}
+            return;
+        }
+        let ticks = |charge: Duration| match charge.checked_sub(handling.das) {
+            Some(repeating) => repeating.as_nanos() / handling.arr.as_nanos() + 1,
+            None => 0,
+        };
+        for _ in ticks(before)..ticks(self.auto_shift.charge) {
+            if !self.shift(direction) {
+                break;
+            }
+        }
+    }
+
+    fn reset_lock_delay_on_move(&mut self) {
+        let current_piece = &mut self.current_piece;
+        if current_piece.state != PieceState::Landed {
+            return;
+        }
+        if let LockReset::Move { max_resets } = self.rules.lock_delay.reset {
+            if current_piece.lock_resets < max_resets {
+                current_piece.lock_resets += 1;
+                current_piece.lock_elapsed = Duration::ZERO;
+            }
+        }
+    }
+
+    /// Runs the lock delay while the piece is resting on something and locks it once it runs
+    /// out. Falling below its lowest row so far starts the delay over.
+    fn update_lock_delay(&mut self, dt: Duration) {
+        let current_piece = &mut self.current_piece;
+        if current_piece.state == PieceState::Locked {
+            return;
+        }
+        if current_piece.position.y < current_piece.lowest_row {
+            current_piece.lowest_row = current_piece.position.y;
+            current_piece.lock_elapsed = Duration::ZERO;
+            current_piece.lock_resets = 0;
+        }
+        let resting = self.board.check_piece_obstructed(
+            current_piece.piece,
+            current_piece.position + IVec2::NEG_Y,
+            current_piece.rotation,
+        );
+        if !resting {
+            current_piece.state = PieceState::Falling;
+            return;
+        }
+        current_piece.state = PieceState::Landed;
+        current_piece.lock_elapsed += dt;
+        if current_piece.lock_elapsed >= self.rules.lock_delay.duration {
+            current_piece.state = PieceState::Locked;
+        }
+    }
+
+    fn gravity_interval(&self, soft_drop: bool) -> Duration {
+        let interval = self.rules.gravity.row_time(self.level);
+        match self.rules.handling.soft_drop {
+            SoftDrop::Factor(factor) if soft_drop => interval / factor.max(1),
+            _ => interval,
+        }
+    }
+
+    /// Moves the piece down a row for every gravity interval that has passed, keeping the
+    /// remainder so the speed doesn't depend on how often this is called. Intervals shorter than a
+    /// frame drop several rows a frame, and 20G goes straight to the floor.
+    /// Soft dropping scores a point or so for every row it pulls the piece down.
+    fn apply_gravity(&mut self, soft_drop: bool, dt: Duration) {
+        if self.current_piece.state == PieceState::Locked {
+            return;
+        }
+        let start = self.current_piece.position.y;
+        let sonic = soft_drop && self.rules.handling.soft_drop == SoftDrop::Sonic;
+        if sonic || self.rules.gravity.g(self.level) >= MAX_GRAVITY {
+            // Already on the floor, and the row time could be too short to divide by.
+            self.current_piece.position = self.ghost_piece().position;
+            self.gravity_elapsed = Duration::ZERO;
+        } else {
+            let interval = self.gravity_interval(soft_drop);
+            self.gravity_elapsed += dt;
+            let rows = self.gravity_elapsed.as_nanos() / interval.as_nanos();
+            self.gravity_elapsed -= interval * rows as u32;
+            let current_piece = &mut self.current_piece;
+            for _ in 0..rows {
+                if self.board.check_piece_obstructed(
+                    current_piece.piece,
+                    current_piece.position + IVec2::NEG_Y,
+                    current_piece.rotation,
+                ) {
+                    break;
+                }
+                current_piece.position.y -= 1;
+            }
+        }
+        let current_piece = &mut self.current_piece;
+        let fallen = (start - current_piece.position.y) as usize;
+        if fallen > 0 {
+            current_piece.last_kick = None;
+            if soft_drop {
+                self.score += self.rules.scoring.soft_drop_points(fallen);
+            }
+        }
+    }
+
+    /// Writes the current piece into the board and scores it. The next piece comes in once any
+    /// line clear and entry delays are over.
+    fn place_piece(&mut self, input: Input, events: &mut Vec<GameEvent>) {
+        let current_piece = self.current_piece;
+        let t_spin = scoring::detect_t_spin(&self.board, &current_piece, current_piece.last_kick);
+        self.board.place_piece(
+            current_piece.piece,
+            current_piece.position,
+            current_piece.rotation,
+        );
+        events.push(GameEvent::PieceLocked(current_piece.piece));
+        if self
+            .rules
+            .top_out
+            .is_lock_out(&current_piece.cells(), self.board.height())
+        {
+            self.end_game(TopOutReason::LockOut, events);
+            return;
+        }
+        let lines = self.assess_board(t_spin, events);
+        if let Goal::Lines(goal) = self.rules.goal {
+            if self.lines >= goal {
+                self.board.clear_full_rows();
+                self.reach_goal(events);
+                return;
+            }
+        }
+        // Turn and hold keys already down as this piece locked were meant for it. They only carry
+        // over to the next one if they're still down once a delay before it is over.
+        let input = Input {
+            rotate_cw_held: false,
+            rotate_ccw_held: false,
+            hold_held: false,
+            ..input
+        };
+        let timing = self.rules.timing;
+        if lines == 0 {
+            self.start_entry(timing.are, input, events);
+        } else if timing.line_clear.is_zero() {
+            self.board.clear_full_rows();
+            self.start_entry(timing.line_are, input, events);
+        } else {
+            self.delay = Some(Delay::LineClear(timing.line_clear));
+        }
+    }
+
+    /// Brings in the next piece once the last one is done with. Initial Hold, Initial Rotation
+    /// and anything buffered happen as it appears, and it only blocks out if whatever ends up in
+    /// play after all of them doesn't fit.
+    fn spawn_next(&mut self, input: Input, events: &mut Vec<GameEvent>) {
+        self.delay = None;
+        self.hold_used = false;
+        let buffered = std::mem::take(&mut self.buffered);
+        let initial_rotation = if !self.rules.initial_rotation {
+            None
+        } else if input.rotate_cw_held {
+            Some(Rotation::Clockwise)
+        } else if input.rotate_ccw_held {
+            Some(Rotation::CounterClockwise)
+        } else {
+            None
+        };
+        let rotation = buffered.rotation.or(initial_rotation);
+        let piece = self.randomizer.next_piece();
+        self.spawn(piece, rotation, events);
+        if buffered.hold || (input.hold_held && self.rules.initial_hold) {
+            self.hold(rotation, events);
+        }
+        self.check_block_out(events);
+    }
+
+    /// Swaps the current piece with the hold slot, or the next piece if the slot is empty. Only
+    /// once per piece, the swapped in piece starts again from the top.
+    fn hold(&mut self, rotation: Option<Rotation>, events: &mut Vec<GameEvent>) {
+        if self.hold_used {
+            return;
+        }
+        self.hold_used = true;
+        let held = self.current_piece.piece;
+        let piece = match self.held_piece.replace(held) {
+            Some(piece) => piece,
+            None => self.randomizer.next_piece(),
+        };
+        events.push(GameEvent::Held(held));
+        self.spawn(piece, rotation, events);
+    }
+
+    /// Puts `piece` at the top, turned `rotation` with kicks on the way in. Whether it fits is up
+    /// to the caller to check, once anything else happening as it spawns is done.
+    fn spawn(&mut self, piece: Piece, rotation: Option<Rotation>, events: &mut Vec<GameEvent>) {
+        self.current_piece = CurrentPiece::new(piece, &self.rules.playfield);
+        self.auto_shift.cut = self.rules.handling.das_cut_delay;
+        events.push(GameEvent::PieceSpawned(piece));
+        if let Some(rotation) = rotation {
+            if let Some((rotated, _)) = self.turned(rotation) {
+                self.current_piece = rotated;
+            }
+        }
+    }
+
+    /// Ends the game if the piece just brought in overlaps the stack.
+    fn check_block_out(&mut self, events: &mut Vec<GameEvent>) {
+        if self.board.check_piece_obstructed(
+            self.current_piece.piece,
+            self.current_piece.position,
+            self.current_piece.rotation,
+        ) {
+            self.end_game(TopOutReason::BlockOut, events);
+        }
+    }
+
+    fn end_game(&mut self, reason: TopOutReason, events: &mut Vec<GameEvent>) {
+        self.top_out = Some(reason);
+        events.push(GameEvent::GameOver(reason));
+    }
+
+    fn reach_goal(&mut self, events: &mut Vec<GameEvent>) {
+        self.goal_reached = true;
+        events.push(GameEvent::GoalReached);
+    }
+
+    /// Scores the lock and returns how many rows it filled, which are left on the board for the
+    /// caller to clear. Combos and back-to-back carry over from one lock to the next, a T-spin that
+    /// clears nothing scores but leaves both alone.
+    fn assess_board(&mut self, t_spin: TSpin, events: &mut Vec<GameEvent>) -> usize {
+        let mut cleared = self.board.clone();
+        let lines = cleared.clear_full_rows();
+        if lines == 0 {
+            self.combo = None;
+            if t_spin != TSpin::None {
+                let action = ClearAction {
+                    t_spin,
+                    ..Default::default()
+                };
+                let points = self.rules.scoring.points(&action, self.level);
+                self.score += points;
+                events.push(GameEvent::Cleared { action, points });
+            }
+            return 0;
+        }
+        let combo = self.combo.map_or(0, |combo| combo + 1);
+        self.combo = Some(combo);
+        let mut action = ClearAction {
+            lines,
+            t_spin,
+            perfect_clear: cleared.is_empty(),
+            back_to_back: false,
+            combo,
+        };
+        action.back_to_back = action.is_difficult() && self.back_to_back;
+        self.back_to_back = action.is_difficult();
+
+        let old_level = self.level;
+        let points = self.rules.scoring.points(&action, self.level);
+        self.lines += lines;
+        self.cleared_lines += lines;
+        if let Some(sprint) = &mut self.sprint {
+            sprint.record(self.lines, self.elapsed);
+        }
+        self.score += points;
+        events.push(GameEvent::Cleared { action, points });
+        let line_threshold = match old_level {
+            0..=8 => old_level * 10 + 10,
+            _ => (old_level * 10 - 50).clamp(100, 200),
+        };
+        if self.cleared_lines >= line_threshold {
+            self.level += 1;
+            self.cleared_lines -= line_threshold;
+        }
+        self.level = std::cmp::min(self.level, MAX_LEVEL);
+        if old_level != self.level {
+            events.push(GameEvent::LevelUp(self.level));
+        }
+        lines
+    }
+}
+
+/// Cheats for debug mode. Nothing in normal play calls these.
+impl Game {
+    /// Sends the current piece to the bottom left corner, if it fits there, and locks it on the
+    /// next step.
+    pub fn debug_teleport(&mut self) {
+        let current_piece = &mut self.current_piece;
+        if !self.board.check_piece_obstructed(
+            current_piece.piece,
+            IVec2::ZERO,
+            current_piece.rotation,
+        ) {
+            current_piece.position = IVec2::ZERO;
+        }
+        current_piece.state = PieceState::Locked;
+    }
+
+    /// Swaps the current piece for `piece`, starting again from the top.
+    pub fn debug_set_piece(&mut self, piece: Piece) -> Vec<GameEvent> {
+        let mut events = vec![];
+        self.spawn(piece, None, &mut events);
+        self.check_block_out(&mut events);
+        events
+    }
+
+    /// Pushes a row of `piece` coloured garbage in from the bottom with a gap in column `hole`.
+    pub fn debug_add_garbage(&mut self, hole: usize, piece: Piece) {
+        self.board.push_garbage_row(hole, piece);
+    }
+
+    /// Goes up a level without clearing anything.
+    pub fn debug_level_up(&mut self) -> Vec<GameEvent> {
+        if self.level == MAX_LEVEL {
+            return vec![];
+        }
+        self.level += 1;
+        self.cleared_lines = 0;
+        vec![GameEvent::LevelUp(self.level)]
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,193 @@
+//! How fast pieces fall at each level. Speeds are in G, rows per frame at 60 frames a second, the
+//! way every version of the game writes them down.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Frames a second the tables are written against.
+pub const FRAME_RATE: u32 = 60;
+/// 20G, as fast as it goes. The piece lands the moment it spawns.
+pub const MAX_GRAVITY: f64 = 20.0;
+
+/// Gravity for each level, in G. Each entry holds from its level until the next entry's.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GravityTable {
+    /// `(level, G)`, sorted by level and starting at level 0.
+    steps: Vec<(usize, f64)>,
+}
+
+impl GravityTable {
+    /// `steps` is `(level, G)` pairs. They get sorted, and anything from level 0 up to the first
+    /// one falls at its speed.
+    pub fn new(mut steps: Vec<(usize, f64)>) -> Self {
+        steps.sort_by_key(|&(level, _)| level);
+        if steps.is_empty() {
+            steps.push((0, 1.0 / FRAME_RATE as f64));
+        }
+        steps[0].0 = 0;
+        Self { steps }
+    }
+
+    /// The guideline curve: `(0.8 - (level - 1) * 0.007) ^ (level - 1)` seconds a row, counting
+    /// levels from 1, up to 20G.
+    pub fn guideline() -> Self {
+        Self::new(
+            (0..=crate::MAX_LEVEL)
+                .map(|level| {
+                    let seconds = (0.8 - level as f64 * 0.007).powi(level as i32);
+                    (
+                        level,
+                        (1.0 / (seconds * FRAME_RATE as f64)).min(MAX_GRAVITY),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// NES NTSC frames per row, one row a frame from level 29.
+    pub fn nes() -> Self {
+        const FRAMES_PER_ROW: [(usize, u32); 15] = [
+            (0, 48),
+            (1, 43),
+            (2, 38),
+            (3, 33),
+            (4, 28),
+            (5, 23),
+            (6, 18),
+            (7, 13),
+            (8, 8),
+            (9, 6),
+            (10, 5),
+            (13, 4),
+            (16, 3),
+            (19, 2),
+            (29, 1),
+        ];
+        Self::new(
+            FRAMES_PER_ROW
+                .iter()
+                .map(|&(level, frames)| (level, 1.0 / frames as f64))
+                .collect(),
+        )
+    }
+
+    /// TGM's internal gravity, in 256ths of a row a frame. TGM counts levels to 999, here every 20
+    /// of those make one of ours, so 20G arrives at level 25. The dip back down at TGM level 200 is
+    /// in the original.
+    pub fn tgm() -> Self {
+        const INTERNAL_GRAVITY: [(usize, u32); 30] = [
+            (0, 4),
+            (30, 6),
+            (35, 8),
+            (40, 10),
+            (50, 12),
+            (60, 16),
+            (70, 32),
+            (80, 48),
+            (90, 64),
+            (100, 80),
+            (120, 96),
+            (140, 112),
+            (160, 128),
+            (170, 144),
+            (200, 4),
+            (220, 32),
+            (230, 64),
+            (233, 96),
+            (236, 128),
+            (239, 160),
+            (243, 192),
+            (247, 224),
+            (251, 256),
+            (300, 512),
+            (330, 768),
+            (360, 1024),
+            (400, 1280),
+            (420, 1024),
+            (450, 768),
+            (500, 5120),
+        ];
+        let mut steps: Vec<(usize, f64)> = vec![];
+        for &(tgm_level, gravity) in INTERNAL_GRAVITY.iter() {
+            let step = (tgm_level / 20, gravity as f64 / 256.0);
+            match steps.last_mut() {
+                // Several TGM steps inside one of our levels, the last one wins.
+                Some(last) if last.0 == step.0 => *last = step,
+                _ => steps.push(step),
+            }
+        }
+        Self::new(steps)
+    }
+
+    /// Speed at `level`, in G.
+    pub fn g(&self, level: usize) -> f64 {
+        self.steps
+            .iter()
+            .rev()
+            .find(|&&(from, _)| from <= level)
+            .map_or(self.steps[0].1, |&(_, g)| g)
+    }
+
+    /// How long a piece takes to fall one row at `level`. Less than a frame above 1G, and as long
+    /// as a `Duration` goes for speeds too slow to fit in one.
+    pub fn row_time(&self, level: usize) -> Duration {
+        let g = self.g(level).max(f64::MIN_POSITIVE);
+        Duration::try_from_secs_f64(1.0 / (g * FRAME_RATE as f64))
+            .unwrap_or(Duration::MAX)
+            .max(Duration::from_nanos(1))
+    }
+}
+
+impl Default for GravityTable {
+    fn default() -> Self {
+        Self::guideline()
+    }
+}
+
+/// A line of a gravity table that didn't make sense.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseGravityError {
+    /// 1-based, like an editor shows it.
+    pub line: usize,
+}
+
+impl fmt::Display for ParseGravityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {} of the gravity table should be `<level> <G>`",
+            self.line
+        )
+    }
+}
+
+impl std::error::Error for ParseGravityError {}
+
+/// Reads a table from text, one `<level> <G>` pair a line. Blank lines and anything after a `#`
+/// are skipped.
+impl FromStr for GravityTable {
+    type Err = ParseGravityError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut steps = vec![];
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = ParseGravityError { line: index + 1 };
+            let mut fields = line.split_whitespace();
+            let level = fields.next().and_then(|level| level.parse().ok());
+            let g = fields.next().and_then(|g| g.parse::<f64>().ok());
+            match (level, g, fields.next()) {
+                (Some(level), Some(g), None) if g.is_finite() && g > 0.0 => steps.push((level, g)),
+                _ => return Err(error),
+            }
+        }
+        if steps.is_empty() {
+            return Err(ParseGravityError { line: 1 });
+        }
+        Ok(Self::new(steps))
+    }
+}
@@ -0,0 +1,28 @@
+//! The rules of the game with no rendering attached. `Game` owns everything needed to play and
+//! is driven one `step` at a time, so tests, bots and tools can run the same rules as the real
+//! game without a window.
+
+pub mod bag;
+pub mod board;
+pub mod game;
+pub mod gravity;
+pub mod piece;
+pub mod randomizer;
+pub mod records;
+pub mod rules;
+pub mod scoring;
+pub mod srs;
+
+pub use bag::Bag;
+pub use board::Board;
+pub use game::{Delay, Game, GameEvent, Input, MAX_LEVEL, TICK};
+pub use gravity::{GravityTable, ParseGravityError};
+pub use piece::{get_piece_meat_positions, CurrentPiece, Piece, PieceState};
+pub use randomizer::{Randomizer, RandomizerKind};
+pub use records::{Pace, ParseRecordsError, Records, SprintRun};
+pub use rules::{
+    Goal, Handling, LockDelay, LockReset, Playfield, Ruleset, SoftDrop, Timing, TopOut,
+    TopOutReason,
+};
+pub use scoring::{ClearAction, ScoringTable, TSpin};
+pub use srs::{HalfTurnKicks, Rotation};
@@ -0,0 +1,263 @@
+use std::time::Duration;
+
+use glam::IVec2;
+use strum_macros::EnumCount as EnumCountMacro;
+
+use crate::rules::Playfield;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumCountMacro)]
+pub enum Piece {
+    I,
+    O,
+    T,
+    S,
+    Z,
+    J,
+    L,
+}
+
+impl Piece {
+    pub const ALL: [Piece; 7] = [
+        Piece::I,
+        Piece::O,
+        Piece::T,
+        Piece::S,
+        Piece::Z,
+        Piece::J,
+        Piece::L,
+    ];
+}
+
+/// Where the current piece is in its life.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PieceState {
+    Falling,
+    /// Resting on something with the lock delay running.
+    Landed,
+    /// Done, it gets written into the board at the end of the step.
+    Locked,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurrentPiece {
+    pub piece: Piece,
+    pub position: IVec2,
+    pub rotation: i32,
+    pub state: PieceState,
+    /// How long it has spent resting since the lock delay last reset.
+    pub lock_elapsed: Duration,
+    /// Moves and rotations that have reset the lock delay so far.
+    pub lock_resets: u32,
+    /// The lowest row it has reached, falling below this resets the lock delay.
+    pub lowest_row: i32,
+    /// The kick the last move used if that move was a rotation, for spotting T-spins.
+    pub last_kick: Option<usize>,
+}
+
+impl CurrentPiece {
+    /// A freshly spawned piece wherever `playfield` says new pieces go.
+    pub fn new(piece: Piece, playfield: &Playfield) -> Self {
+        Self {
+            rotation: playfield.spawn_rotation.rem_euclid(4),
+            ..Self::at(piece, playfield.spawn_position())
+        }
+    }
+
+    /// A freshly spawned piece in its spawn state at `position`.
+    pub fn at(piece: Piece, position: IVec2) -> Self {
+        Self {
+            piece,
+            position,
+            rotation: 0,
+            state: PieceState::Falling,
+            lock_elapsed: Duration::ZERO,
+            lock_resets: 0,
+            lowest_row: position.y,
+            last_kick: None,
+        }
+    }
+
+    pub fn cells(&self) -> [IVec2; 4] {
+        get_piece_meat_positions(self.piece, self.position, self.rotation)
+    }
+}
+
+/// The four cells of `piece` in the given rotation state, with `offset` as the bottom left of its
+/// bounding box. States follow SRS: 0 is spawn, then each step is a clockwise turn, and the
+/// piece turns about the middle of a 3x3 box (4x4 for the I) so kicks line up with the tables.
+#[allow(clippy::identity_op)]
+pub fn get_piece_meat_positions(piece: Piece, offset: IVec2, rotation: i32) -> [IVec2; 4] {
+    let x = offset.x;
+    let y = offset.y;
+    match piece {
+        Piece::I => match rotation {
+            0 => [
+                IVec2 { x: x + 0, y: y + 2 },
+                IVec2 { x: x + 1, y: y + 2 },
+                IVec2 { x: x + 2, y: y + 2 },
+                IVec2 { x: x + 3, y: y + 2 },
+            ],
+            1 => [
+                IVec2 { x: x + 2, y: y + 3 },
+                IVec2 { x: x + 2, y: y + 2 },
+                IVec2 { x: x + 2, y: y + 1 },
+                IVec2 { x: x + 2, y: y + 0 },
+            ],
+            2 => [
+                IVec2 { x: x + 0, y: y + 1 },
+                IVec2 { x: x + 1, y: y + 1 },
+                IVec2 { x: x + 2, y: y + 1 },
+                IVec2 { x: x + 3, y: y + 1 },
+            ],
+            3 => [
+                IVec2 { x: x + 1, y: y + 3 },
+                IVec2 { x: x + 1, y: y + 2 },
+                IVec2 { x: x + 1, y: y + 1 },
+                IVec2 { x: x + 1, y: y + 0 },
+            ],
+            _ => unreachable!(),
+        },
+        // Top right of its 3x3 box, so it spawns over the same rows and middle columns as the rest.
+        Piece::O => [
+            IVec2 { x: x + 1, y: y + 1 },
+            IVec2 { x: x + 1, y: y + 2 },
+            IVec2 { x: x + 2, y: y + 1 },
+            IVec2 { x: x + 2, y: y + 2 },
+        ],
+        Piece::T => match rotation {
+            0 => [
+                IVec2 { x: x + 1, y: y + 2 },
+                IVec2 { x: x + 0, y: y + 1 },
+                IVec2 { x: x + 1, y: y + 1 },
+                IVec2 { x: x + 2, y: y + 1 },
+            ],
+            1 => [
+                IVec2 { x: x + 1, y: y + 2 },
+                IVec2 { x: x + 1, y: y + 1 },
+                IVec2 { x: x + 2, y: y + 1 },
+                IVec2 { x: x + 1, y: y + 0 },
+            ],
+            2 => [
+                IVec2 { x: x + 0, y: y + 1 },
+                IVec2 { x: x + 1, y: y + 1 },
+                IVec2 { x: x + 2, y: y + 1 },
+                IVec2 { x: x + 1, y: y + 0 },
+            ],
+            3 => [
+                IVec2 { x: x + 1, y: y + 2 },
+                IVec2 { x: x + 0, y: y + 1 },
+                IVec2 { x: x + 1, y: y + 1 },
+                IVec2 { x: x + 1, y: y + 0 },
+            ],
+            _ => unreachable!(),
+        },
+        Piece::S => match rotation {
+            0 => [
+                IVec2 { x: x + 1, y: y + 2 },
+                IVec2 { x: x + 2, y: y + 2 },
+                IVec2 { x: x + 0, y: y + 1 },
+                IVec2 { x: x + 1, y: y + 1 },
+            ],
+            1 => [
+                IVec2 { x: x + 1, y: y + 2 },
+                IVec2 { x: x + 1, y: y + 1 },
+                IVec2 { x: x + 2, y: y + 1 },
+                IVec2 { x: x + 2, y: y + 0 },
+            ],
+            2 => [
+                IVec2 { x: x + 1, y: y + 1 },
+                IVec2 { x: x + 2, y: y + 1 },
+                IVec2 { x: x + 0, y: y + 0 },
+                IVec2 { x: x + 1, y: y + 0 },
+            ],
+            3 => [
+                IVec2 { x: x + 0, y: y + 2 },
+                IVec2 { x: x + 0, y: y + 1 },
+                IVec2 { x: x + 1, y: y + 1 },
+                IVec2 { x: x + 1, y: y + 0 },
+            ],
+            _ => unreachable!(),
+        },
+        Piece::Z => match rotation {
+            0 => [
+                IVec2 { x: x + 0, y: y + 2 },
+                IVec2 { x: x + 1, y: y + 2 },
+                IVec2 { x: x + 1, y: y + 1 },
+                IVec2 { x: x + 2, y: y + 1 },
+            ],
+            1 => [
+                IVec2 { x: x + 2, y: y + 2 },
+                IVec2 { x: x + 1, y: y + 1 },
+                IVec2 { x: x + 2, y: y + 1 },
+                IVec2 { x: x + 1, y: y + 0 },
+            ],
+            2 => [
+                IVec2 { x: x + 0, y: y + 1 },
+                IVec2 { x: x + 1, y: y + 1 },
+                IVec2 { x: x + 1, y: y + 0 },
+                IVec2 { x: x + 2, y: y + 0 },
+            ],
+            3 => [
+                IVec2 { x: x + 1, y: y + 2 },
+                IVec2 { x: x + 0, y: y + 1 },
+                IVec2 { x: x + 1, y: y + 1 },
+                IVec2 { x: x + 0, y: y + 0 },
+            ],
+            _ => unreachable!(),
+        },
+        Piece::J => match rotation {
+            0 => [
+                IVec2 { x: x + 0, y: y + 2 },
+                IVec2 { x: x + 0, y: y + 1 },
+                IVec2 { x: x + 1, y: y + 1 },
+                IVec2 { x: x + 2, y: y + 1 },
+            ],
+            1 => [
+                IVec2 { x: x + 1, y: y + 2 },
+                IVec2 { x: x + 2, y: y + 2 },
+                IVec2 { x: x + 1, y: y + 1 },
+                IVec2 { x: x + 1, y: y + 0 },
+            ],
+            2 => [
+                IVec2 { x: x + 0, y: y + 1 },
+                IVec2 { x: x + 1, y: y + 1 },
+                IVec2 { x: x + 2, y: y + 1 },
+                IVec2 { x: x + 2, y: y + 0 },
+            ],
+            3 => [
+                IVec2 { x: x + 1, y: y + 2 },
+                IVec2 { x: x + 1, y: y + 1 },
+                IVec2 { x: x + 0, y: y + 0 },
+                IVec2 { x: x + 1, y: y + 0 },
+            ],
+            _ => unreachable!(),
+        },
+        Piece::L => match rotation {
+            0 => [
+                IVec2 { x: x + 2, y: y + 2 },
+                IVec2 { x: x + 0, y: y + 1 },
+                IVec2 { x: x + 1, y: y + 1 },
+                IVec2 { x: x + 2, y: y + 1 },
+            ],
+            1 => [
+                IVec2 { x: x + 1, y: y + 2 },
+                IVec2 { x: x + 1, y: y + 1 },
+                IVec2 { x: x + 1, y: y + 0 },
+                IVec2 { x: x + 2, y: y + 0 },
+            ],
+            2 => [
+                IVec2 { x: x + 0, y: y + 1 },
+                IVec2 { x: x + 1, y: y + 1 },
+                IVec2 { x: x + 2, y: y + 1 },
+                IVec2 { x: x + 0, y: y + 0 },
+            ],
+            3 => [
+                IVec2 { x: x + 0, y: y + 2 },
+                IVec2 { x: x + 1, y: y + 2 },
+                IVec2 { x: x + 1, y: y + 1 },
+                IVec2 { x: x + 1, y: y + 0 },
+            ],
+            _ => unreachable!(),
+        },
+    }
+}
@@ -0,0 +1,252 @@
+//! Where the pieces come from. Every randomizer is seeded so a game can be replayed, and looking
+//! ahead never changes what gets dealt.
+
+use std::collections::VecDeque;
+use std::fmt::Debug;
+
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+use crate::bag::Bag;
+use crate::piece::Piece;
+
+pub trait Randomizer: Debug + Send + Sync {
+    /// Deals the next piece.
+    fn next_piece(&mut self) -> Piece;
+
+    /// The next `count` pieces `next_piece` will deal, without dealing them.
+    fn peek(&self, count: usize) -> Vec<Piece>;
+
+    fn box_clone(&self) -> Box<dyn Randomizer>;
+}
+
+impl Clone for Box<dyn Randomizer> {
+    fn clone(&self) -> Self {
+        self.box_clone()
+    }
+}
+
+/// Seeded randomizers are deterministic, so dealing from a copy shows exactly what is coming.
+pub(crate) fn peek_by_dealing<R: Randomizer + Clone>(randomizer: &R, count: usize) -> Vec<Piece> {
+    let mut ahead = randomizer.clone();
+    (0..count).map(|_| ahead.next_piece()).collect()
+}
+
+/// Which randomizer a game deals from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RandomizerKind {
+    /// One of each piece per bag.
+    #[default]
+    SevenBag,
+    /// Two of each piece per bag.
+    FourteenBag,
+    /// Every piece equally likely every time.
+    Random,
+    /// Rerolls once if it comes up with the last piece.
+    Nes,
+    /// Rolls up to `rolls` times for a piece that isn't one of the last four. TGM uses 4, TGM2
+    /// uses 6.
+    Tgm { rolls: u32 },
+    /// TGM3, history of four plus a pool that leans towards pieces that haven't shown up in a
+    /// while.
+    Tgm3,
+}
+
+impl RandomizerKind {
+    pub fn build(self, seed: u64) -> Box<dyn Randomizer> {
+        match self {
+            RandomizerKind::SevenBag => Box::new(Bag::new(seed)),
+            RandomizerKind::FourteenBag => Box::new(Bag::with_copies(seed, 2)),
+            RandomizerKind::Random => Box::new(PureRandom::new(seed)),
+            RandomizerKind::Nes => Box::new(Nes::new(seed)),
+            RandomizerKind::Tgm { rolls } => Box::new(Tgm::new(seed, rolls)),
+            RandomizerKind::Tgm3 => Box::new(Tgm3::new(seed)),
+        }
+    }
+}
+
+fn random_piece(rng: &mut ChaCha8Rng) -> Piece {
+    Piece::ALL[rng.gen_range(0..Piece::ALL.len())]
+}
+
+#[derive(Debug, Clone)]
+pub struct PureRandom {
+    rng: ChaCha8Rng,
+}
+
+impl PureRandom {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Randomizer for PureRandom {
+    fn next_piece(&mut self) -> Piece {
+        random_piece(&mut self.rng)
+    }
+
+    fn peek(&self, count: usize) -> Vec<Piece> {
+        peek_by_dealing(self, count)
+    }
+
+    fn box_clone(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+/// The NES rolls one of eight, the eighth meaning "roll again". Coming up with the same piece as
+/// last time also rolls again, and the second roll is kept whatever it is.
+#[derive(Debug, Clone)]
+pub struct Nes {
+    rng: ChaCha8Rng,
+    last: Option<Piece>,
+}
+
+impl Nes {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            last: None,
+        }
+    }
+}
+
+impl Randomizer for Nes {
+    fn next_piece(&mut self) -> Piece {
+        let roll = self.rng.gen_range(0..=Piece::ALL.len());
+        let piece = match Piece::ALL.get(roll) {
+            Some(&piece) if Some(piece) != self.last => piece,
+            _ => random_piece(&mut self.rng),
+        };
+        self.last = Some(piece);
+        piece
+    }
+
+    fn peek(&self, count: usize) -> Vec<Piece> {
+        peek_by_dealing(self, count)
+    }
+
+    fn box_clone(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+/// The first piece is never S, Z or O, so the game can't start with an overhang.
+const TGM_FIRST_PIECES: [Piece; 4] = [Piece::I, Piece::J, Piece::L, Piece::T];
+
+/// Remembers the last four pieces and rolls up to `rolls` times for one that isn't among them,
+/// keeping the last roll if they all are.
+#[derive(Debug, Clone)]
+pub struct Tgm {
+    rng: ChaCha8Rng,
+    rolls: u32,
+    history: VecDeque<Piece>,
+    first: bool,
+}
+
+impl Tgm {
+    pub fn new(seed: u64, rolls: u32) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            rolls: rolls.max(1),
+            history: VecDeque::from([Piece::Z, Piece::S, Piece::S, Piece::Z]),
+            first: true,
+        }
+    }
+}
+
+impl Randomizer for Tgm {
+    fn next_piece(&mut self) -> Piece {
+        let piece = if self.first {
+            self.first = false;
+            TGM_FIRST_PIECES[self.rng.gen_range(0..TGM_FIRST_PIECES.len())]
+        } else {
+            let mut piece = random_piece(&mut self.rng);
+            for _ in 1..self.rolls {
+                if !self.history.contains(&piece) {
+                    break;
+                }
+                piece = random_piece(&mut self.rng);
+            }
+            piece
+        };
+        self.history.pop_front();
+        self.history.push_back(piece);
+        piece
+    }
+
+    fn peek(&self, count: usize) -> Vec<Piece> {
+        peek_by_dealing(self, count)
+    }
+
+    fn box_clone(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
+
+const TGM3_ROLLS: usize = 6;
+
+/// TGM3 rolls from a pool of 35, five of each piece to start with. Every time a piece is dealt
+/// (and every time a roll lands in the history) its slot in the pool goes to whichever piece has
+/// been gone the longest, so droughts fix themselves.
+#[derive(Debug, Clone)]
+pub struct Tgm3 {
+    rng: ChaCha8Rng,
+    history: VecDeque<Piece>,
+    pool: Vec<Piece>,
+    /// Pieces by how long since they were last dealt, longest first.
+    drought: Vec<Piece>,
+    first: bool,
+}
+
+impl Tgm3 {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            history: VecDeque::from([Piece::S, Piece::Z, Piece::S, Piece::Z]),
+            pool: Piece::ALL.repeat(5),
+            drought: Piece::ALL.to_vec(),
+            first: true,
+        }
+    }
+
+    fn dealt(&mut self, piece: Piece) {
+        self.drought.retain(|&other| other != piece);
+        self.drought.push(piece);
+        self.history.pop_front();
+        self.history.push_back(piece);
+    }
+}
+
+impl Randomizer for Tgm3 {
+    fn next_piece(&mut self) -> Piece {
+        if self.first {
+            self.first = false;
+            let piece = TGM_FIRST_PIECES[self.rng.gen_range(0..TGM_FIRST_PIECES.len())];
+            self.dealt(piece);
+            return piece;
+        }
+        let mut index = 0;
+        for roll in 0..TGM3_ROLLS {
+            index = self.rng.gen_range(0..self.pool.len());
+            if !self.history.contains(&self.pool[index]) || roll == TGM3_ROLLS - 1 {
+                break;
+            }
+            self.pool[index] = self.drought[0];
+        }
+        let piece = self.pool[index];
+        self.dealt(piece);
+        self.pool[index] = self.drought[0];
+        piece
+    }
+
+    fn peek(&self, count: usize) -> Vec<Piece> {
+        peek_by_dealing(self, count)
+    }
+
+    fn box_clone(&self) -> Box<dyn Randomizer> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,189 @@
+//! Personal bests, kept between games. They save as a few lines of text so the game can keep them
+//! in a file and people can read them.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// When each line of a Sprint was cleared, from the start of the game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SprintRun {
+    /// Lines to clear.
+    pub goal: usize,
+    /// One per line cleared so far. Lines cleared together share a split.
+    splits: Vec<Duration>,
+}
+
+impl SprintRun {
+    pub fn new(goal: usize) -> Self {
+        Self {
+            goal,
+            splits: vec![],
+        }
+    }
+
+    /// Notes that `lines` lines in total had been cleared by `at`.
+    pub fn record(&mut self, lines: usize, at: Duration) {
+        while self.splits.len() < lines.min(self.goal) {
+            self.splits.push(at);
+        }
+    }
+
+    pub fn splits(&self) -> &[Duration] {
+        &self.splits
+    }
+
+    /// The final time, `None` until the goal is reached.
+    pub fn time(&self) -> Option<Duration> {
+        match self.splits.len() >= self.goal {
+            true => self.splits.last().copied(),
+            false => None,
+        }
+    }
+
+    /// How this run compares to `best` at the last line it cleared, `None` before the first line
+    /// or if `best` never got that far.
+    pub fn pace(&self, best: &SprintRun) -> Option<Pace> {
+        let line = self.splits.len().checked_sub(1)?;
+        let ours = self.splits[line];
+        let theirs = *best.splits.get(line)?;
+        Some(match ours <= theirs {
+            true => Pace::Ahead(theirs - ours),
+            false => Pace::Behind(ours - theirs),
+        })
+    }
+}
+
+/// How far a run is in front of or behind another one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pace {
+    Ahead(Duration),
+    Behind(Duration),
+}
+
+/// The best run for each kind of game that has one.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Records {
+    sprints: Vec<SprintRun>,
+    /// Best Ultra score for each time limit.
+    ultras: Vec<(Duration, usize)>,
+}
+
+impl Records {
+    /// The fastest finished Sprint to `goal` lines.
+    pub fn best_sprint(&self, goal: usize) -> Option<&SprintRun> {
+        self.sprints.iter().find(|run| run.goal == goal)
+    }
+
+    /// Keeps `run` if it finished faster than the best for its goal, and says whether it did.
+    pub fn submit_sprint(&mut self, run: &SprintRun) -> bool {
+        let Some(time) = run.time() else {
+            return false;
+        };
+        match self.sprints.iter_mut().find(|best| best.goal == run.goal) {
+            Some(best) if best.time().is_some_and(|best| best <= time) => false,
+            Some(best) => {
+                *best = run.clone();
+                true
+            }
+            None => {
+                self.sprints.push(run.clone());
+                true
+            }
+        }
+    }
+
+    /// The highest score from an Ultra lasting `limit`.
+    pub fn best_ultra(&self, limit: Duration) -> Option<usize> {
+        self.ultras
+            .iter()
+            .find(|&&(time, _)| time == limit)
+            .map(|&(_, score)| score)
+    }
+
+    /// Keeps `score` if it beats the best for an Ultra lasting `limit`, and says whether it did.
+    pub fn submit_ultra(&mut self, limit: Duration, score: usize) -> bool {
+        match self.ultras.iter_mut().find(|(time, _)| *time == limit) {
+            Some((_, best)) if *best >= score => false,
+            Some((_, best)) => {
+                *best = score;
+                true
+            }
+            None => {
+                self.ultras.push((limit, score));
+                true
+            }
+        }
+    }
+}
+
+/// A line of a records file that didn't make sense.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRecordsError {
+    /// 1-based, like an editor shows it.
+    pub line: usize,
+}
+
+impl fmt::Display for ParseRecordsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {} of the records isn't a record", self.line)
+    }
+}
+
+impl std::error::Error for ParseRecordsError {}
+
+/// One record a line. A Sprint is `sprint <goal>` then each split in milliseconds, an Ultra is
+/// `ultra <time limit in milliseconds> <score>`.
+impl fmt::Display for Records {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for run in &self.sprints {
+            write!(f, "sprint {}", run.goal)?;
+            for split in &run.splits {
+                write!(f, " {}", split.as_millis())?;
+            }
+            writeln!(f)?;
+        }
+        for (limit, score) in &self.ultras {
+            writeln!(f, "ultra {} {score}", limit.as_millis())?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Records {
+    type Err = ParseRecordsError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut records = Records::default();
+        for (index, line) in text.lines().enumerate() {
+            let error = ParseRecordsError { line: index + 1 };
+            let mut fields = line.split_whitespace();
+            match fields.next() {
+                None => continue,
+                Some("sprint") => {
+                    let goal = fields.next().and_then(|goal| goal.parse().ok());
+                    let splits: Option<Vec<Duration>> = fields
+                        .map(|split| split.parse().ok().map(Duration::from_millis))
+                        .collect();
+                    let (Some(goal), Some(splits)) = (goal, splits) else {
+                        return Err(error);
+                    };
+                    if splits.len() != goal {
+                        return Err(error);
+                    }
+                    records.sprints.push(SprintRun { goal, splits });
+                }
+                Some("ultra") => {
+                    let limit = fields.next().and_then(|limit| limit.parse().ok());
+                    let score = fields.next().and_then(|score| score.parse().ok());
+                    let (Some(limit), Some(score), None) = (limit, score, fields.next()) else {
+                        return Err(error);
+                    };
+                    records.ultras.push((Duration::from_millis(limit), score));
+                }
+                Some(_) => return Err(error),
+            }
+        }
+        Ok(records)
+    }
+}
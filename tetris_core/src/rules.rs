@@ -0,0 +1,240 @@
+//! Knobs that differ between versions of the game. `Ruleset::default()` is how we play it.
+
+use std::time::Duration;
+
+use glam::IVec2;
+
+use crate::gravity::GravityTable;
+use crate::randomizer::RandomizerKind;
+use crate::scoring::ScoringTable;
+use crate::srs::HalfTurnKicks;
+
+/// Why the game ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopOutReason {
+    /// A new piece spawned on top of the stack.
+    BlockOut,
+    /// A piece locked above the top of the playfield.
+    LockOut,
+}
+
+/// Which top-out rules end the game. A blocked spawn always does, the variants differ in how much
+/// of a piece has to lock above the playfield before that counts too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TopOut {
+    /// Lock out only when the whole piece is above the playfield.
+    #[default]
+    Guideline,
+    /// Lock out as soon as any cell of the piece is above the playfield.
+    Partial,
+    /// Never lock out, the stack can carry on up into the buffer zone.
+    BlockOutOnly,
+}
+
+impl TopOut {
+    /// Whether locking `cells` on a playfield `height` rows tall ends the game.
+    pub fn is_lock_out(self, cells: &[IVec2], height: usize) -> bool {
+        let above = cells.iter().filter(|cell| cell.y >= height as i32).count();
+        match self {
+            TopOut::Guideline => above == cells.len(),
+            TopOut::Partial => above > 0,
+            TopOut::BlockOutOnly => false,
+        }
+    }
+}
+
+/// What restarts the lock delay once a piece has landed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockReset {
+    /// Every successful move or rotation restarts it, up to `max_resets` times per piece. Falling
+    /// to a new lowest row gives the moves back.
+    Move { max_resets: u32 },
+    /// Only falling to a new lowest row restarts it.
+    Step,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockDelay {
+    pub duration: Duration,
+    pub reset: LockReset,
+}
+
+impl Default for LockDelay {
+    fn default() -> Self {
+        Self {
+            duration: Duration::from_millis(500),
+            reset: LockReset::Move { max_resets: 15 },
+        }
+    }
+}
+
+/// How fast soft drop pulls the piece down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoftDrop {
+    /// Gravity runs this many times faster while soft dropping.
+    Factor(u32),
+    /// The piece goes straight to the bottom but doesn't lock, unlike a hard drop.
+    Sonic,
+}
+
+/// Delayed Auto Shift: holding left or right moves once, waits `das`, then moves again every
+/// `arr`. An `arr` of zero goes straight to the wall.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Handling {
+    pub das: Duration,
+    pub arr: Duration,
+    /// DAS cut delay: auto shift stops for this long after a rotation or a new piece, so a charged
+    /// DAS doesn't throw the next piece into the wall before you mean it to.
+    pub das_cut_delay: Duration,
+    pub soft_drop: SoftDrop,
+    /// How long a rotate press that couldn't happen yet, like one the piece has no room for, is
+    /// kept around to try again.
+    pub input_buffer: Duration,
+}
+
+impl Default for Handling {
+    fn default() -> Self {
+        Self {
+            das: Duration::from_millis(167),
+            arr: Duration::from_millis(33),
+            das_cut_delay: Duration::ZERO,
+            soft_drop: SoftDrop::Factor(20),
+            input_buffer: Duration::from_millis(67),
+        }
+    }
+}
+
+/// Pauses between one piece locking and the next one turning up. Zero all round is how we've
+/// always played, pieces come in the moment the last one locks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Timing {
+    /// Entry delay (ARE) after a lock that clears nothing.
+    pub are: Duration,
+    /// Entry delay after a lock that clears lines, counted from when the rows vanish.
+    pub line_are: Duration,
+    /// How long full rows stay on the board before they vanish.
+    pub line_clear: Duration,
+}
+
+impl Timing {
+    /// NES timing, near enough. The real ARE is 10 to 18 frames depending on how high the piece
+    /// locked.
+    pub fn nes() -> Self {
+        Self {
+            are: frames(10),
+            line_are: frames(10),
+            line_clear: frames(18),
+        }
+    }
+
+    /// The first Tetris: The Grand Master.
+    pub fn tgm() -> Self {
+        Self {
+            are: frames(30),
+            line_are: frames(30),
+            line_clear: frames(41),
+        }
+    }
+}
+
+fn frames(count: u32) -> Duration {
+    Duration::from_secs(1) * count / crate::gravity::FRAME_RATE
+}
+
+/// The size of the board and where new pieces turn up on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Playfield {
+    pub width: usize,
+    /// Visible rows.
+    pub height: usize,
+    /// Hidden rows above the visible ones, the vanishing zone pieces spawn into and can stack up
+    /// into before locking out.
+    pub buffer_rows: usize,
+    /// Left column of a new piece's box, `None` centres it.
+    pub spawn_column: Option<i32>,
+    /// Bottom row of a new piece's box, `None` puts it just under the top of the visible rows.
+    pub spawn_row: Option<i32>,
+    /// SRS rotation state new pieces start in, 0 is flat side down.
+    pub spawn_rotation: i32,
+}
+
+impl Playfield {
+    /// The 10x20 board every guideline game uses.
+    pub fn guideline() -> Self {
+        Self {
+            width: 10,
+            ..Default::default()
+        }
+    }
+
+    /// Where the box of a newly spawned piece goes.
+    pub fn spawn_position(&self) -> IVec2 {
+        IVec2 {
+            x: self.spawn_column.unwrap_or((self.width as i32 - 3) / 2),
+            y: self.spawn_row.unwrap_or(self.height as i32 - 3),
+        }
+    }
+}
+
+impl Default for Playfield {
+    fn default() -> Self {
+        Self {
+            width: 15,
+            height: 20,
+            buffer_rows: 20,
+            spawn_column: None,
+            spawn_row: None,
+            spawn_rotation: 0,
+        }
+    }
+}
+
+/// What finishes a game other than topping out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Goal {
+    /// Play on until topping out.
+    #[default]
+    Endless,
+    /// Sprint: done as soon as this many lines are cleared.
+    Lines(usize),
+    /// Ultra: done once this much time has been played, for the best score in it.
+    Time(Duration),
+}
+
+#[derive(Debug, Clone)]
+pub struct Ruleset {
+    pub playfield: Playfield,
+    pub top_out: TopOut,
+    pub gravity: GravityTable,
+    pub timing: Timing,
+    /// Initial Hold: keeping the hold key down while a piece spawns holds it straight away.
+    pub initial_hold: bool,
+    /// Initial Rotation: keeping a rotate key down while a piece spawns turns it on the way in.
+    pub initial_rotation: bool,
+    /// Kicks for the 180 button, `None` turns it off.
+    pub half_turn: Option<HalfTurnKicks>,
+    pub lock_delay: LockDelay,
+    pub handling: Handling,
+    pub randomizer: RandomizerKind,
+    pub scoring: ScoringTable,
+    pub goal: Goal,
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        Self {
+            playfield: Playfield::default(),
+            top_out: TopOut::default(),
+            gravity: GravityTable::default(),
+            timing: Timing::default(),
+            initial_hold: true,
+            initial_rotation: true,
+            half_turn: Some(HalfTurnKicks::default()),
+            lock_delay: LockDelay::default(),
+            handling: Handling::default(),
+            randomizer: RandomizerKind::default(),
+            scoring: ScoringTable::default(),
+            goal: Goal::default(),
+        }
+    }
+}
@@ -0,0 +1,175 @@
+//! Points for clears and drops. Spotting T-spins lives here too since nothing else cares about
+//! them.
+
+use glam::IVec2;
+
+use crate::board::Board;
+use crate::piece::{CurrentPiece, Piece};
+use crate::srs;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TSpin {
+    #[default]
+    None,
+    Mini,
+    Full,
+}
+
+/// Everything about a lock that the scoring table needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ClearAction {
+    pub lines: usize,
+    pub t_spin: TSpin,
+    /// The board was left completely empty.
+    pub perfect_clear: bool,
+    /// This clear and the last one that cleared lines were both difficult.
+    pub back_to_back: bool,
+    /// How many line clears in a row came before this one, 0 for the first.
+    pub combo: usize,
+}
+
+impl ClearAction {
+    /// Tetrises and T-spins that clear lines, the clears that keep back-to-back going.
+    pub fn is_difficult(&self) -> bool {
+        self.lines == 4 || (self.lines > 0 && self.t_spin != TSpin::None)
+    }
+
+    /// What to call it on screen, like "T-SPIN DOUBLE". `None` if it's nothing worth shouting
+    /// about.
+    pub fn name(&self) -> Option<String> {
+        let lines = match self.lines {
+            0 => None,
+            1 => Some("SINGLE"),
+            2 => Some("DOUBLE"),
+            3 => Some("TRIPLE"),
+            _ => Some("TETRIS"),
+        };
+        let t_spin = match self.t_spin {
+            TSpin::None => None,
+            TSpin::Mini => Some("T-SPIN MINI"),
+            TSpin::Full => Some("T-SPIN"),
+        };
+        match (t_spin, lines) {
+            (Some(t_spin), Some(lines)) => Some(format!("{t_spin} {lines}")),
+            (Some(name), None) | (None, Some(name)) => Some(name.to_string()),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Which scoring table to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScoringTable {
+    /// Modern guideline scoring, with T-spins, combos, back-to-back and perfect clears.
+    #[default]
+    Guideline,
+    /// 40/100/300/1200 times the level, and nothing else.
+    Nes,
+}
+
+impl ScoringTable {
+    /// Points for locking a piece, `level` counting from 0.
+    pub fn points(self, action: &ClearAction, level: usize) -> usize {
+        let multiplier = level + 1;
+        match self {
+            ScoringTable::Guideline => {
+                let mut points = match (action.t_spin, action.lines) {
+                    (TSpin::None, 0) => 0,
+                    (TSpin::None, 1) => 100,
+                    (TSpin::None, 2) => 300,
+                    (TSpin::None, 3) => 500,
+                    (TSpin::None, _) => 800,
+                    (TSpin::Mini, 0) => 100,
+                    (TSpin::Mini, 1) => 200,
+                    (TSpin::Mini, _) => 400,
+                    (TSpin::Full, 0) => 400,
+                    (TSpin::Full, 1) => 800,
+                    (TSpin::Full, 2) => 1200,
+                    (TSpin::Full, _) => 1600,
+                };
+                if action.back_to_back {
+                    points = points * 3 / 2;
+                }
+                if action.lines > 0 {
+                    points += 50 * action.combo;
+                }
+                if action.perfect_clear {
+                    points += match action.lines {
+                        1 => 800,
+                        2 => 1200,
+                        3 => 1800,
+                        _ if action.back_to_back => 3200,
+                        _ => 2000,
+                    };
+                }
+                points * multiplier
+            }
+            ScoringTable::Nes => {
+                let points = match action.lines {
+                    0 => 0,
+                    1 => 40,
+                    2 => 100,
+                    3 => 300,
+                    _ => 1200,
+                };
+                points * multiplier
+            }
+        }
+    }
+
+    pub fn soft_drop_points(self, rows: usize) -> usize {
+        rows
+    }
+
+    pub fn hard_drop_points(self, rows: usize) -> usize {
+        match self {
+            ScoringTable::Guideline => rows * 2,
+            ScoringTable::Nes => 0,
+        }
+    }
+}
+
+/// The corners of the T's 3x3 box, the two it points at first.
+fn t_corners(rotation: i32) -> [IVec2; 4] {
+    let (front, back) = match rotation {
+        0 => (
+            [IVec2::new(0, 2), IVec2::new(2, 2)],
+            [IVec2::new(0, 0), IVec2::new(2, 0)],
+        ),
+        1 => (
+            [IVec2::new(2, 2), IVec2::new(2, 0)],
+            [IVec2::new(0, 2), IVec2::new(0, 0)],
+        ),
+        2 => (
+            [IVec2::new(0, 0), IVec2::new(2, 0)],
+            [IVec2::new(0, 2), IVec2::new(2, 2)],
+        ),
+        _ => (
+            [IVec2::new(0, 2), IVec2::new(0, 0)],
+            [IVec2::new(2, 2), IVec2::new(2, 0)],
+        ),
+    };
+    [front[0], front[1], back[0], back[1]]
+}
+
+/// The 3-corner rule: a T that got where it is by rotating, with three of the four corners around
+/// its middle blocked, is a T-spin. It's a full one if both corners it points at are blocked, or if
+/// it took the last kick to get there, otherwise it's a mini. `kick` is the index of the kick
+/// used by the last move if that move was a rotation.
+pub fn detect_t_spin(board: &Board, piece: &CurrentPiece, kick: Option<usize>) -> TSpin {
+    let Some(kick) = kick else {
+        return TSpin::None;
+    };
+    if piece.piece != Piece::T {
+        return TSpin::None;
+    }
+    let blocked = t_corners(piece.rotation).map(|corner| board.is_blocked(piece.position + corner));
+    if blocked.iter().filter(|&&blocked| blocked).count() < 3 {
+        return TSpin::None;
+    }
+    if (blocked[0] && blocked[1]) || kick == srs::KICK_COUNT - 1 {
+        TSpin::Full
+    } else {
+        TSpin::Mini
+    }
+}
@@ -0,0 +1,253 @@
+//! Super Rotation System. A rotation tries each kick for the piece and the two states in order
+//! and takes the first one that fits, if none do the piece stays put.
+
+use glam::IVec2;
+
+use crate::board::Board;
+use crate::piece::{CurrentPiece, Piece};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    Clockwise,
+    CounterClockwise,
+    /// 180, not part of SRS proper. Its kicks come from a [`HalfTurnKicks`] table.
+    Half,
+}
+
+impl Rotation {
+    /// The state `rotation` ends up in after turning this way.
+    pub fn apply(self, rotation: i32) -> i32 {
+        match self {
+            Rotation::Clockwise => (rotation + 1) % 4,
+            Rotation::CounterClockwise => (rotation + 3) % 4,
+            Rotation::Half => (rotation + 2) % 4,
+        }
+    }
+}
+
+const fn kick(x: i32, y: i32) -> IVec2 {
+    IVec2 { x, y }
+}
+
+/// How many kicks each JLSTZ and I rotation has, the last one is the big 1x2 one.
+pub const KICK_COUNT: usize = 5;
+
+// Indexed by starting state, clockwise first then counter-clockwise. Up is +y like the board.
+const JLSTZ_KICKS: [[[IVec2; KICK_COUNT]; 2]; 4] = [
+    [
+        [
+            kick(0, 0),
+            kick(-1, 0),
+            kick(-1, 1),
+            kick(0, -2),
+            kick(-1, -2),
+        ], // 0 -> R
+        [kick(0, 0), kick(1, 0), kick(1, 1), kick(0, -2), kick(1, -2)], // 0 -> L
+    ],
+    [
+        [kick(0, 0), kick(1, 0), kick(1, -1), kick(0, 2), kick(1, 2)], // R -> 2
+        [kick(0, 0), kick(1, 0), kick(1, -1), kick(0, 2), kick(1, 2)], // R -> 0
+    ],
+    [
+        [kick(0, 0), kick(1, 0), kick(1, 1), kick(0, -2), kick(1, -2)], // 2 -> L
+        [
+            kick(0, 0),
+            kick(-1, 0),
+            kick(-1, 1),
+            kick(0, -2),
+            kick(-1, -2),
+        ], // 2 -> R
+    ],
+    [
+        [
+            kick(0, 0),
+            kick(-1, 0),
+            kick(-1, -1),
+            kick(0, 2),
+            kick(-1, 2),
+        ], // L -> 0
+        [
+            kick(0, 0),
+            kick(-1, 0),
+            kick(-1, -1),
+            kick(0, 2),
+            kick(-1, 2),
+        ], // L -> 2
+    ],
+];
+
+const I_KICKS: [[[IVec2; KICK_COUNT]; 2]; 4] = [
+    [
+        [
+            kick(0, 0),
+            kick(-2, 0),
+            kick(1, 0),
+            kick(-2, -1),
+            kick(1, 2),
+        ], // 0 -> R
+        [
+            kick(0, 0),
+            kick(-1, 0),
+            kick(2, 0),
+            kick(-1, 2),
+            kick(2, -1),
+        ], // 0 -> L
+    ],
+    [
+        [
+            kick(0, 0),
+            kick(-1, 0),
+            kick(2, 0),
+            kick(-1, 2),
+            kick(2, -1),
+        ], // R -> 2
+        [
+            kick(0, 0),
+            kick(2, 0),
+            kick(-1, 0),
+            kick(2, 1),
+            kick(-1, -2),
+        ], // R -> 0
+    ],
+    [
+        [
+            kick(0, 0),
+            kick(2, 0),
+            kick(-1, 0),
+            kick(2, 1),
+            kick(-1, -2),
+        ], // 2 -> L
+        [
+            kick(0, 0),
+            kick(1, 0),
+            kick(-2, 0),
+            kick(1, -2),
+            kick(-2, 1),
+        ], // 2 -> R
+    ],
+    [
+        [
+            kick(0, 0),
+            kick(1, 0),
+            kick(-2, 0),
+            kick(1, -2),
+            kick(-2, 1),
+        ], // L -> 0
+        [
+            kick(0, 0),
+            kick(-2, 0),
+            kick(1, 0),
+            kick(-2, -1),
+            kick(1, 2),
+        ], // L -> 2
+    ],
+];
+
+const O_KICKS: [IVec2; 1] = [kick(0, 0)];
+
+// TETR.IO's SRS+ 180 kicks, the same for every piece. Indexed by starting state.
+const SRS_PLUS_HALF_KICKS: [[IVec2; 6]; 4] = [
+    [
+        kick(0, 0),
+        kick(0, 1),
+        kick(1, 1),
+        kick(-1, 1),
+        kick(1, 0),
+        kick(-1, 0),
+    ], // 0 -> 2
+    [
+        kick(0, 0),
+        kick(1, 0),
+        kick(1, 2),
+        kick(1, 1),
+        kick(0, 2),
+        kick(0, 1),
+    ], // R -> L
+    [
+        kick(0, 0),
+        kick(0, -1),
+        kick(-1, -1),
+        kick(1, -1),
+        kick(-1, 0),
+        kick(1, 0),
+    ], // 2 -> 0
+    [
+        kick(0, 0),
+        kick(-1, 0),
+        kick(-1, 2),
+        kick(-1, 1),
+        kick(0, 2),
+        kick(0, 1),
+    ], // L -> R
+];
+
+/// Which kicks a 180 tries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HalfTurnKicks {
+    /// SRS+, as TETR.IO has it.
+    #[default]
+    SrsPlus,
+    /// Turns where it is or not at all.
+    InPlace,
+}
+
+impl HalfTurnKicks {
+    /// The offsets to try, in order, when turning `piece` half way round from state `from`.
+    pub fn kicks(self, piece: Piece, from: i32) -> &'static [IVec2] {
+        match (self, piece) {
+            (HalfTurnKicks::InPlace, _) | (_, Piece::O) => &O_KICKS,
+            (HalfTurnKicks::SrsPlus, _) => &SRS_PLUS_HALF_KICKS[from as usize],
+        }
+    }
+}
+
+/// The offsets to try, in order, when turning `piece` out of state `from`. A 180 gets the SRS+
+/// kicks, [`rotate_with`] can use a different table.
+pub fn kicks(piece: Piece, from: i32, direction: Rotation) -> &'static [IVec2] {
+    let column = match direction {
+        Rotation::Clockwise => 0,
+        Rotation::CounterClockwise => 1,
+        Rotation::Half => return HalfTurnKicks::default().kicks(piece, from),
+    };
+    match piece {
+        Piece::O => &O_KICKS,
+        Piece::I => &I_KICKS[from as usize][column],
+        _ => &JLSTZ_KICKS[from as usize][column],
+    }
+}
+
+/// Turns `piece` on `board`, returning where it ended up and the index of the kick that got it
+/// there. `None` means every kick was blocked.
+pub fn rotate(
+    board: &Board,
+    piece: &CurrentPiece,
+    direction: Rotation,
+) -> Option<(CurrentPiece, usize)> {
+    rotate_with(board, piece, direction, HalfTurnKicks::default())
+}
+
+/// [`rotate`], with `half_turn` kicks for a 180.
+pub fn rotate_with(
+    board: &Board,
+    piece: &CurrentPiece,
+    direction: Rotation,
+    half_turn: HalfTurnKicks,
+) -> Option<(CurrentPiece, usize)> {
+    let new_rotation = direction.apply(piece.rotation);
+    let kicks = match direction {
+        Rotation::Half => half_turn.kicks(piece.piece, piece.rotation),
+        _ => kicks(piece.piece, piece.rotation, direction),
+    };
+    kicks
+        .iter()
+        .enumerate()
+        .find(|(_, offset)| {
+            !board.check_piece_obstructed(piece.piece, piece.position + **offset, new_rotation)
+        })
+        .map(|(index, offset)| {
+            let mut rotated = *piece;
+            rotated.position += *offset;
+            rotated.rotation = new_rotation;
+            (rotated, index)
+        })
+}
@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use glam::IVec2;
+use tetris_core::{get_piece_meat_positions, Game, GameEvent, Input, Piece, Ruleset, MAX_LEVEL};
+
+#[test]
+fn teleport_locks_in_the_bottom_left() {
+    let mut game = Game::seeded(Ruleset::default(), 1);
+    let piece = *game.current_piece();
+    game.debug_teleport();
+    let events = game.step(Input::default(), Duration::ZERO);
+    assert!(events.contains(&GameEvent::PieceLocked(piece.piece)));
+    for cell in get_piece_meat_positions(piece.piece, IVec2::ZERO, piece.rotation) {
+        assert!(game.board().is_filled(cell.x as usize, cell.y as usize));
+    }
+}
+
+#[test]
+fn set_piece_respawns_as_that_piece() {
+    let mut game = Game::seeded(Ruleset::default(), 1);
+    let next = game.next_pieces(3);
+    for piece in Piece::ALL {
+        assert_eq!(
+            game.debug_set_piece(piece),
+            [GameEvent::PieceSpawned(piece)]
+        );
+        assert_eq!(game.current_piece().piece, piece);
+    }
+    assert_eq!(game.next_pieces(3), next, "the queue is left alone");
+}
+
+#[test]
+fn garbage_comes_in_from_the_bottom() {
+    let mut game = Game::seeded(Ruleset::default(), 1);
+    game.board_mut().set(4, 0, Some(Piece::T));
+    game.debug_add_garbage(2, Piece::O);
+
+    let board = game.board();
+    for col in 0..board.width() {
+        assert_eq!(board.is_filled(col, 0), col != 2);
+    }
+    assert_eq!(board.get(4, 1), Some(Piece::T));
+}
+
+#[test]
+fn level_skip_stops_at_the_top() {
+    let mut game = Game::seeded(Ruleset::default(), 1);
+    assert_eq!(game.debug_level_up(), [GameEvent::LevelUp(1)]);
+    while !game.debug_level_up().is_empty() {}
+    assert_eq!(game.level(), MAX_LEVEL);
+}
@@ -0,0 +1,71 @@
+//! Helpers shared by the integration tests. Each only uses some of them.
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use glam::IVec2;
+use tetris_core::{Board, CurrentPiece, Game, Input, Piece, Ruleset};
+
+/// Seed for test games, so every run plays the same pieces.
+pub const SEED: u64 = 9;
+
+pub fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+/// A seeded game on the default rules.
+pub fn default_game() -> Game {
+    Game::seeded(Ruleset::default(), SEED)
+}
+
+/// A seeded game on the default rules with whatever `tweak` changes.
+pub fn game_with(tweak: impl FnOnce(&mut Ruleset)) -> Game {
+    let mut rules = Ruleset::default();
+    tweak(&mut rules);
+    Game::seeded(rules, SEED)
+}
+
+pub fn hard_drop() -> Input {
+    Input {
+        hard_drop: true,
+        ..Default::default()
+    }
+}
+
+pub fn hold() -> Input {
+    Input {
+        hold: true,
+        hold_held: true,
+        ..Default::default()
+    }
+}
+
+/// Fills the bottom row apart from where the current piece will land, so dropping it clears one.
+pub fn set_up_single(game: &mut Game) {
+    let landing = game.ghost_piece().cells();
+    for col in 0..game.board().width() {
+        if !landing.contains(&IVec2::new(col as i32, 0)) {
+            game.board_mut().set(col, 0, Some(Piece::O));
+        }
+    }
+}
+
+/// Builds a board from rows of `#` and `.`, top row first.
+pub fn board_from(rows: &[&str]) -> Board {
+    let mut board = Board::new(rows[0].len(), rows.len());
+    for (row_index, row) in rows.iter().rev().enumerate() {
+        for (col, cell) in row.chars().enumerate() {
+            if cell == '#' {
+                board.set(col, row_index, Some(Piece::J));
+            }
+        }
+    }
+    board
+}
+
+pub fn piece_at(piece: Piece, x: i32, y: i32, rotation: i32) -> CurrentPiece {
+    CurrentPiece {
+        rotation,
+        ..CurrentPiece::at(piece, IVec2::new(x, y))
+    }
+}
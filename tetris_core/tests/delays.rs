@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use tetris_core::{Delay, Game, GameEvent, Input, Timing};
+
+mod common;
+
+use common::{default_game, game_with, hard_drop, ms, set_up_single};
+
+fn spawned(events: &[GameEvent]) -> bool {
+    events
+        .iter()
+        .any(|event| matches!(event, GameEvent::PieceSpawned(_)))
+}
+
+#[test]
+fn no_timing_spawns_straight_away() {
+    let mut game = default_game();
+    let events = game.step(hard_drop(), Duration::ZERO);
+    assert!(spawned(&events));
+    assert_eq!(game.delay(), None);
+}
+
+#[test]
+fn entry_delay_holds_back_the_next_piece() {
+    let mut game = game_with(|rules| rules.timing.are = ms(100));
+    let events = game.step(hard_drop(), Duration::ZERO);
+    assert!(!spawned(&events));
+    assert_eq!(game.delay(), Some(Delay::Entry(ms(100))));
+
+    assert!(!spawned(&game.step(Input::default(), ms(99))));
+    assert!(spawned(&game.step(Input::default(), ms(1))));
+    assert_eq!(game.delay(), None);
+}
+
+#[test]
+fn full_rows_stay_until_the_line_clear_delay_is_over() {
+    let mut game = game_with(|rules| {
+        rules.timing = Timing {
+            line_clear: ms(200),
+            line_are: ms(50),
+            are: ms(1000),
+        }
+    });
+    set_up_single(&mut game);
+    let events = game.step(hard_drop(), Duration::ZERO);
+    // Scored as soon as it locks.
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, GameEvent::Cleared { .. })));
+    let full = |game: &Game| (0..game.board().width()).all(|col| game.board().is_filled(col, 0));
+    assert!(full(&game));
+    assert_eq!(game.lines(), 1);
+
+    game.step(Input::default(), ms(150));
+    assert!(full(&game));
+    game.step(Input::default(), ms(50));
+    assert!(!full(&game));
+    assert_eq!(game.delay(), Some(Delay::Entry(ms(50))));
+    assert!(spawned(&game.step(Input::default(), ms(50))));
+}
+
+#[test]
+fn presses_during_the_delay_apply_to_the_next_piece() {
+    let mut game = game_with(|rules| rules.timing.are = ms(100));
+    game.step(hard_drop(), Duration::ZERO);
+    let next = game.next_piece();
+    let press = Input {
+        rotate_cw: true,
+        hold: true,
+        ..Default::default()
+    };
+    game.step(press, ms(10));
+    game.step(Input::default(), ms(90));
+    assert_eq!(game.held_piece(), Some(next));
+    assert_eq!(game.current_piece().rotation, 1);
+}
+
+#[test]
+fn das_charges_during_the_delay() {
+    let mut game = game_with(|rules| rules.timing.are = ms(300));
+    game.step(hard_drop(), Duration::ZERO);
+    let left = Input {
+        left: true,
+        left_held: true,
+        ..Default::default()
+    };
+    let held = Input {
+        left_held: true,
+        ..Default::default()
+    };
+    game.step(left, Duration::ZERO);
+    game.step(held, ms(300));
+    let spawn_x = game.current_piece().position.x;
+    // Already charged, so the new piece starts repeating at once.
+    game.step(held, ms(33));
+    assert!(game.current_piece().position.x < spawn_x);
+}
@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use glam::IVec2;
+use tetris_core::{Board, Game, Input, Piece, Ruleset};
+
+#[test]
+fn ghost_is_where_a_hard_drop_lands() {
+    let mut game = Game::seeded(Ruleset::default(), 5);
+    let ghost = game.ghost_piece();
+    assert!(ghost.position.y < game.current_piece().position.y);
+
+    let hard_drop = Input {
+        hard_drop: true,
+        ..Default::default()
+    };
+    game.step(hard_drop, Duration::ZERO);
+    for cell in ghost.cells() {
+        assert!(game.board().is_filled(cell.x as usize, cell.y as usize));
+    }
+}
+
+#[test]
+fn landing_position_stops_on_the_stack() {
+    let mut board = Board::new(10, 20);
+    board.set(4, 5, Some(Piece::I));
+    // The O fills the top right of its box, so a box at column 3 puts it over the filled cell.
+    assert_eq!(
+        board.landing_position(Piece::O, IVec2::new(3, 15), 0),
+        IVec2::new(3, 5)
+    );
+    // Clear of it, the bottom of the box ends up a row under the floor.
+    assert_eq!(
+        board.landing_position(Piece::O, IVec2::new(6, 15), 0),
+        IVec2::new(6, -1)
+    );
+}
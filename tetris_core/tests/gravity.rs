@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use tetris_core::{GravityTable, Input, ParseGravityError, PieceState};
+
+mod common;
+
+use common::game_with;
+
+/// A 60th of a second, rounded up like a real frame time would be.
+fn frame() -> Duration {
+    Duration::from_micros(16_667)
+}
+
+#[test]
+fn presets_match_their_games() {
+    let nes = GravityTable::nes();
+    assert_eq!(nes.g(0), 1.0 / 48.0);
+    assert_eq!(nes.g(12), 1.0 / 5.0);
+    assert_eq!(nes.g(29), 1.0);
+
+    let guideline = GravityTable::guideline();
+    assert_eq!(guideline.row_time(0), Duration::from_secs(1));
+    let level_5 = 0.8f64 - 4.0 * 0.007;
+    assert!((guideline.g(4) - 1.0 / (level_5.powi(4) * 60.0)).abs() < 1e-9);
+    assert_eq!(guideline.g(29), 20.0);
+
+    let tgm = GravityTable::tgm();
+    assert_eq!(tgm.g(0), 4.0 / 256.0);
+    assert_eq!(tgm.g(10), 4.0 / 256.0);
+    assert_eq!(tgm.g(25), 20.0);
+}
+
+#[test]
+fn tables_load_from_text() {
+    let table: GravityTable = "# level G\n0 0.5\n\n10 2 # fast\n".parse().unwrap();
+    assert_eq!(table.g(9), 0.5);
+    assert_eq!(table.g(10), 2.0);
+    assert_eq!(table.g(100), 2.0);
+
+    assert_eq!(
+        "0 0.5\n3 fast".parse::<GravityTable>(),
+        Err(ParseGravityError { line: 2 })
+    );
+    assert!("".parse::<GravityTable>().is_err());
+}
+
+#[test]
+fn sub_cell_gravity_adds_up_over_frames() {
+    // A row every four frames.
+    let mut game = game_with(|rules| rules.gravity = "0 0.25".parse().unwrap());
+    let start = game.current_piece().position.y;
+    for _ in 0..3 {
+        game.step(Input::default(), frame());
+    }
+    assert_eq!(game.current_piece().position.y, start);
+    game.step(Input::default(), frame());
+    assert_eq!(game.current_piece().position.y, start - 1);
+}
+
+#[test]
+fn multi_cell_gravity_falls_several_rows_a_frame() {
+    let mut game = game_with(|rules| rules.gravity = "0 3".parse().unwrap());
+    let start = game.current_piece().position.y;
+    game.step(Input::default(), frame());
+    assert_eq!(game.current_piece().position.y, start - 3);
+}
+
+#[test]
+fn twenty_g_lands_straight_away() {
+    let mut game = game_with(|rules| rules.gravity = "0 20".parse().unwrap());
+    let ghost = game.ghost_piece();
+    game.step(Input::default(), Duration::ZERO);
+    assert_eq!(game.current_piece().position, ghost.position);
+    assert_eq!(game.current_piece().state, PieceState::Landed);
+}
+
+#[test]
+fn tiny_gravity_never_falls_instead_of_overflowing() {
+    let table: GravityTable = "0 1e-22".parse().unwrap();
+    assert_eq!(table.row_time(0), Duration::MAX);
+    let mut game = game_with(|rules| rules.gravity = table);
+    let start = game.current_piece().position.y;
+    game.step(Input::default(), Duration::from_secs(60));
+    assert_eq!(game.current_piece().position.y, start);
+}
+
+#[test]
+fn soft_dropping_under_huge_gravity_lands_instead_of_dividing_by_zero() {
+    let mut game = game_with(|rules| rules.gravity = "0 1000000".parse().unwrap());
+    let ghost = game.ghost_piece();
+    let soft_drop = Input {
+        soft_drop: true,
+        ..Default::default()
+    };
+    game.step(soft_drop, frame());
+    assert_eq!(game.current_piece().position, ghost.position);
+}
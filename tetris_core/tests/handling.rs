@@ -0,0 +1,167 @@
+use std::time::Duration;
+
+use tetris_core::{Game, GameEvent, Input, Piece, PieceState, SoftDrop};
+
+mod common;
+
+use common::{default_game, game_with, ms};
+
+fn press_left() -> Input {
+    Input {
+        left: true,
+        left_held: true,
+        ..Default::default()
+    }
+}
+
+fn hold_left() -> Input {
+    Input {
+        left_held: true,
+        ..Default::default()
+    }
+}
+
+/// Holds left for `total` in steps of `step` and returns how far the piece went.
+fn hold_for(game: &mut Game, total: Duration, step: Duration) -> i32 {
+    let start = game.current_piece().position.x;
+    let mut elapsed = Duration::ZERO;
+    while elapsed < total {
+        game.step(hold_left(), step);
+        elapsed += step;
+    }
+    start - game.current_piece().position.x
+}
+
+#[test]
+fn das_waits_before_repeating() {
+    let mut game = default_game();
+    let start = game.current_piece().position.x;
+    game.step(press_left(), Duration::ZERO);
+    assert_eq!(game.current_piece().position.x, start - 1);
+
+    assert_eq!(hold_for(&mut game, ms(166), ms(1)), 0);
+    assert_eq!(hold_for(&mut game, ms(1), ms(1)), 1);
+    assert_eq!(hold_for(&mut game, ms(33), ms(1)), 1);
+}
+
+#[test]
+fn auto_shift_does_not_depend_on_frame_rate() {
+    let moved: Vec<i32> = [1, 10, 25, 50, 250]
+        .into_iter()
+        .map(|step| {
+            let mut game = default_game();
+            game.step(press_left(), Duration::ZERO);
+            hold_for(&mut game, ms(250), ms(step))
+        })
+        .collect();
+    // (250 - 167) / 33 + 1
+    assert_eq!(moved, [3; 5]);
+}
+
+#[test]
+fn a_hard_drop_locks_where_the_ghost_was() {
+    let mut game = default_game();
+    game.step(press_left(), Duration::ZERO);
+    hold_for(&mut game, ms(166), ms(1));
+    // A bump under the right end, so moving off it afterwards would leave the piece in the air.
+    let cells = game.current_piece().cells();
+    let right = cells.iter().map(|cell| cell.x).max().unwrap();
+    game.board_mut().set(right as usize, 0, Some(Piece::O));
+    let ghost = game.ghost_piece().cells();
+
+    // DAS repeats during this step, after the drop has already locked the piece.
+    let drop = Input {
+        hard_drop: true,
+        ..hold_left()
+    };
+    game.step(drop, ms(10));
+    for cell in ghost {
+        assert!(game.board().get(cell.x as usize, cell.y as usize).is_some());
+    }
+}
+
+#[test]
+fn zero_arr_goes_straight_to_the_wall() {
+    let mut game = game_with(|rules| rules.handling.arr = Duration::ZERO);
+    game.step(press_left(), Duration::ZERO);
+    hold_for(&mut game, ms(167), ms(167));
+    let leftmost = game.current_piece().cells().map(|cell| cell.x);
+    assert_eq!(leftmost.into_iter().min(), Some(0));
+}
+
+#[test]
+fn switching_direction_recharges() {
+    let mut game = default_game();
+    game.step(press_left(), Duration::ZERO);
+    hold_for(&mut game, ms(200), ms(10));
+
+    let x = game.current_piece().position.x;
+    let both = Input {
+        right: true,
+        right_held: true,
+        left_held: true,
+        ..Default::default()
+    };
+    game.step(both, Duration::ZERO);
+    assert_eq!(game.current_piece().position.x, x + 1);
+    let both = Input {
+        right: false,
+        ..both
+    };
+    game.step(both, ms(100));
+    assert_eq!(game.current_piece().position.x, x + 1);
+
+    // Letting go of right hands it back to left, which moves once and charges again.
+    game.step(hold_left(), Duration::ZERO);
+    assert_eq!(game.current_piece().position.x, x);
+    assert_eq!(hold_for(&mut game, ms(100), ms(10)), 0);
+}
+
+#[test]
+fn das_cut_delay_pauses_after_rotating() {
+    let mut game = game_with(|rules| rules.handling.das_cut_delay = ms(100));
+    game.step(press_left(), Duration::ZERO);
+    assert_eq!(hold_for(&mut game, ms(167), ms(1)), 1);
+
+    let rotate = Input {
+        rotate_cw: true,
+        left_held: true,
+        ..Default::default()
+    };
+    game.step(rotate, Duration::ZERO);
+    assert_eq!(hold_for(&mut game, ms(100), ms(1)), 0);
+    assert_eq!(hold_for(&mut game, ms(33), ms(1)), 1);
+}
+
+#[test]
+fn soft_drop_factor_speeds_up_gravity() {
+    let soft_drop = Input {
+        soft_drop: true,
+        ..Default::default()
+    };
+    let mut game = default_game();
+    let start = game.current_piece().position.y;
+    game.step(Input::default(), ms(410));
+    assert_eq!(game.current_piece().position.y, start);
+
+    // A second a row at level 0, 20 times faster.
+    let mut game = default_game();
+    game.step(soft_drop, ms(410));
+    assert_eq!(game.current_piece().position.y, start - 8);
+}
+
+#[test]
+fn sonic_soft_drop_lands_without_locking() {
+    let mut game = game_with(|rules| rules.handling.soft_drop = SoftDrop::Sonic);
+    let ghost = game.ghost_piece();
+    let soft_drop = Input {
+        soft_drop: true,
+        ..Default::default()
+    };
+    let events = game.step(soft_drop, ms(1));
+    assert!(!events
+        .iter()
+        .any(|event| matches!(event, GameEvent::PieceLocked(_))));
+    assert_eq!(game.current_piece().position, ghost.position);
+    assert_eq!(game.current_piece().state, PieceState::Landed);
+}
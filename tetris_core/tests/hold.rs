@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use tetris_core::{GameEvent, Input};
+
+mod common;
+
+use common::{default_game, game_with, hard_drop, hold, ms};
+
+#[test]
+fn first_hold_takes_the_next_piece() {
+    let mut game = default_game();
+    let first = game.current_piece().piece;
+    let next = game.next_piece();
+
+    let events = game.step(hold(), Duration::ZERO);
+    assert!(events.contains(&GameEvent::Held(first)));
+    assert_eq!(game.held_piece(), Some(first));
+    assert_eq!(game.current_piece().piece, next);
+    assert!(!game.can_hold());
+}
+
+#[test]
+fn hold_only_works_once_per_piece() {
+    let mut game = default_game();
+    game.step(hold(), Duration::ZERO);
+    let current = game.current_piece().piece;
+    let held = game.held_piece();
+
+    assert!(game.step(hold(), Duration::ZERO).is_empty());
+    assert_eq!(game.current_piece().piece, current);
+    assert_eq!(game.held_piece(), held);
+}
+
+#[test]
+fn locking_frees_up_the_hold_and_swaps_back() {
+    let mut game = default_game();
+    let first = game.current_piece().piece;
+    game.step(hold(), Duration::ZERO);
+    game.step(hard_drop(), Duration::from_secs(1));
+    assert!(game.can_hold());
+
+    let current = game.current_piece().piece;
+    game.step(hold(), Duration::ZERO);
+    assert_eq!(game.current_piece().piece, first);
+    assert_eq!(game.held_piece(), Some(current));
+}
+
+#[test]
+fn initial_hold_swaps_as_the_piece_spawns() {
+    let mut game = game_with(|rules| rules.timing.are = ms(100));
+    game.step(hard_drop(), Duration::ZERO);
+    let holding = Input {
+        hold_held: true,
+        ..Default::default()
+    };
+
+    let events = game.step(holding, ms(100));
+    let spawned = events.iter().find_map(|event| match event {
+        GameEvent::PieceSpawned(piece) => Some(*piece),
+        _ => None,
+    });
+    assert_eq!(game.held_piece(), spawned);
+    assert_ne!(game.held_piece(), None);
+    assert!(!game.can_hold());
+}
+
+#[test]
+fn a_hold_still_down_from_the_last_piece_does_not_swap_the_next() {
+    let mut game = default_game();
+    game.step(hold(), Duration::ZERO);
+    let held = game.held_piece();
+    let drop_holding = Input {
+        hold_held: true,
+        ..hard_drop()
+    };
+
+    game.step(drop_holding, Duration::ZERO);
+    assert_eq!(game.held_piece(), held);
+    assert!(game.can_hold());
+}
+
+#[test]
+fn initial_hold_can_be_turned_off() {
+    let mut game = game_with(|rules| {
+        rules.timing.are = ms(100);
+        rules.initial_hold = false;
+    });
+    game.step(hard_drop(), Duration::ZERO);
+    let holding = Input {
+        hold_held: true,
+        ..Default::default()
+    };
+
+    game.step(holding, ms(100));
+    assert_eq!(game.held_piece(), None);
+    assert!(game.can_hold());
+}
@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+use tetris_core::{CurrentPiece, Game, GameEvent, Input, Piece, Rotation, Ruleset, Timing};
+
+mod common;
+
+use common::{default_game, game_with, hard_drop, hold, ms};
+
+#[test]
+fn holding_rotate_turns_the_next_piece_as_it_spawns() {
+    let mut game = game_with(|rules| rules.timing.are = ms(100));
+    let holding_cw = Input {
+        rotate_cw_held: true,
+        ..Default::default()
+    };
+    game.step(hard_drop(), Duration::ZERO);
+    game.step(holding_cw, ms(100));
+    assert_eq!(game.current_piece().rotation, 1);
+
+    let holding_ccw = Input {
+        rotate_ccw_held: true,
+        ..Default::default()
+    };
+    game.step(hard_drop(), Duration::ZERO);
+    game.step(holding_ccw, ms(100));
+    assert_eq!(game.current_piece().rotation, 3);
+}
+
+#[test]
+fn a_turn_still_held_from_the_last_piece_does_not_turn_the_next() {
+    let mut game = default_game();
+    game.step(
+        Input {
+            rotate_cw: true,
+            rotate_cw_held: true,
+            ..Default::default()
+        },
+        Duration::ZERO,
+    );
+    let drop_holding_cw = Input {
+        rotate_cw_held: true,
+        ..hard_drop()
+    };
+    game.step(drop_holding_cw, Duration::ZERO);
+    assert_eq!(game.current_piece().rotation, 0);
+}
+
+#[test]
+fn initial_rotation_can_be_turned_off() {
+    let mut game = game_with(|rules| {
+        rules.timing.are = ms(100);
+        rules.initial_rotation = false;
+    });
+    let holding_cw = Input {
+        rotate_cw_held: true,
+        ..Default::default()
+    };
+    game.step(hard_drop(), Duration::ZERO);
+    game.step(holding_cw, ms(100));
+    assert_eq!(game.current_piece().rotation, 0);
+}
+
+#[test]
+fn turning_on_the_way_in_can_dodge_a_block_out() {
+    let rules = Ruleset {
+        timing: Timing {
+            are: ms(100),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let spawn = CurrentPiece::new(Piece::T, &rules.playfield);
+    let turned = CurrentPiece {
+        rotation: Rotation::Clockwise.apply(spawn.rotation),
+        ..spawn
+    };
+    // A seed with a T coming next.
+    let mut game = (0..)
+        .map(|seed| Game::seeded(rules.clone(), seed))
+        .find(|game| game.next_piece() == Piece::T)
+        .unwrap();
+    game.step(hard_drop(), Duration::ZERO);
+    // Block wherever the next piece would go flat but not once it's turned.
+    for cell in spawn.cells() {
+        if !turned.cells().contains(&cell) {
+            game.board_mut()
+                .set(cell.x as usize, cell.y as usize, Some(Piece::O));
+        }
+    }
+    let holding_cw = Input {
+        rotate_cw_held: true,
+        ..Default::default()
+    };
+    game.step(holding_cw, ms(100));
+    assert!(!game.is_over());
+    assert_eq!(game.current_piece().rotation, 1);
+}
+
+#[test]
+fn holding_on_the_way_in_can_dodge_a_block_out() {
+    let mut game = game_with(|rules| rules.timing.are = ms(100));
+    game.step(hard_drop(), Duration::ZERO);
+    let playfield = game.rules().playfield;
+    let [coming, swapped_in] = game.next_pieces(2)[..] else {
+        unreachable!()
+    };
+    let coming = CurrentPiece::new(coming, &playfield).cells();
+    let swapped_in = CurrentPiece::new(swapped_in, &playfield).cells();
+    // Block wherever the next piece goes but not the one holding swaps in for it.
+    let mut blocked = false;
+    for cell in coming {
+        if !swapped_in.contains(&cell) {
+            game.board_mut()
+                .set(cell.x as usize, cell.y as usize, Some(Piece::O));
+            blocked = true;
+        }
+    }
+    assert!(blocked, "the seed deals the same piece twice");
+    let holding = Input {
+        hold_held: true,
+        ..Default::default()
+    };
+    game.step(holding, ms(100));
+    assert!(!game.is_over());
+    assert_eq!(game.current_piece().cells(), swapped_in);
+}
+
+#[test]
+fn a_hold_refused_for_this_piece_is_not_kept_for_the_next() {
+    let mut game = default_game();
+    game.step(hold(), Duration::ZERO);
+    // Already held this piece, so this one does nothing.
+    game.step(Input::default(), Duration::ZERO);
+    game.step(hold(), Duration::ZERO);
+    let events = game.step(hard_drop(), ms(20));
+    assert!(!events
+        .iter()
+        .any(|event| matches!(event, GameEvent::Held(_))));
+}
+
+/// Fills the board around the current piece so it has nowhere to turn, then presses clockwise and
+/// empties the board again.
+fn turn_with_no_room(game: &mut Game) {
+    let cells = game.current_piece().cells();
+    let (width, height) = (game.board().width(), game.board().total_height());
+    let board = game.board_mut();
+    for x in 0..width {
+        for y in 0..height {
+            if !cells.contains(&[x as i32, y as i32].into()) {
+                board.set(x, y, Some(Piece::O));
+            }
+        }
+    }
+    let press_cw = Input {
+        rotate_cw: true,
+        ..Default::default()
+    };
+    game.step(press_cw, Duration::ZERO);
+    assert_eq!(game.current_piece().rotation, 0);
+    game.board_mut().clear();
+}
+
+#[test]
+fn a_turn_with_no_room_goes_through_once_there_is() {
+    let mut game = default_game();
+    turn_with_no_room(&mut game);
+    game.step(Input::default(), ms(20));
+    assert_eq!(game.current_piece().rotation, 1);
+}
+
+#[test]
+fn buffered_presses_go_stale() {
+    let mut game = default_game();
+    turn_with_no_room(&mut game);
+    game.step(Input::default(), ms(100));
+    assert_eq!(game.current_piece().rotation, 0);
+}
@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use tetris_core::{Game, GameEvent, Input, LockDelay, LockReset, PieceState};
+
+mod common;
+
+use common::{default_game, game_with, ms};
+
+fn idle() -> Input {
+    Input::default()
+}
+
+/// Alternates left and right so the piece never runs into a wall.
+fn wiggle(count: usize) -> Input {
+    Input {
+        left: count.is_multiple_of(2),
+        right: !count.is_multiple_of(2),
+        ..Default::default()
+    }
+}
+
+fn locked(events: &[GameEvent]) -> bool {
+    events
+        .iter()
+        .any(|event| matches!(event, GameEvent::PieceLocked(_)))
+}
+
+/// Lets gravity bring the current piece down to the floor in small steps, so landing only puts
+/// a few milliseconds on the lock delay.
+fn land(game: &mut Game) {
+    for _ in 0..10_000 {
+        if game.current_piece().state == PieceState::Landed {
+            return;
+        }
+        game.step(idle(), ms(10));
+    }
+    panic!("piece never landed");
+}
+
+#[test]
+fn landing_waits_for_the_lock_delay() {
+    let mut game = default_game();
+    land(&mut game);
+
+    assert!(!locked(&game.step(idle(), ms(400))));
+    assert_eq!(game.current_piece().state, PieceState::Landed);
+    assert!(locked(&game.step(idle(), ms(100))));
+}
+
+#[test]
+fn hard_drop_locks_straight_away() {
+    let mut game = default_game();
+    let hard_drop = Input {
+        hard_drop: true,
+        ..Default::default()
+    };
+    assert!(locked(&game.step(hard_drop, Duration::ZERO)));
+}
+
+#[test]
+fn moving_restarts_the_lock_delay() {
+    let mut game = default_game();
+    land(&mut game);
+
+    for count in 0..5 {
+        assert!(!locked(&game.step(idle(), ms(400))));
+        assert!(!locked(&game.step(wiggle(count), Duration::ZERO)));
+    }
+    assert_eq!(game.current_piece().lock_resets, 5);
+    assert!(locked(&game.step(idle(), ms(500))));
+}
+
+#[test]
+fn move_resets_run_out() {
+    let mut game = game_with(|rules| {
+        rules.lock_delay = LockDelay {
+            duration: ms(500),
+            reset: LockReset::Move { max_resets: 2 },
+        }
+    });
+    land(&mut game);
+
+    game.step(idle(), ms(400));
+    game.step(wiggle(0), Duration::ZERO);
+    game.step(idle(), ms(400));
+    game.step(wiggle(1), Duration::ZERO);
+    game.step(idle(), ms(400));
+    // Out of resets, this one doesn't buy any time.
+    game.step(wiggle(2), Duration::ZERO);
+    assert!(locked(&game.step(idle(), ms(100))));
+}
+
+#[test]
+fn step_reset_ignores_moves() {
+    let mut game = game_with(|rules| {
+        rules.lock_delay = LockDelay {
+            duration: ms(500),
+            reset: LockReset::Step,
+        }
+    });
+    land(&mut game);
+
+    game.step(idle(), ms(400));
+    game.step(wiggle(0), Duration::ZERO);
+    assert_eq!(game.current_piece().lock_resets, 0);
+    assert!(locked(&game.step(idle(), ms(100))));
+}
+
+#[test]
+fn zero_lock_delay_locks_on_landing() {
+    let mut game = game_with(|rules| {
+        rules.lock_delay = LockDelay {
+            duration: Duration::ZERO,
+            reset: LockReset::Step,
+        }
+    });
+    // Gravity lands the piece within a board height of steps and it locks the same step.
+    let landed = (0..game.board().height()).any(|_| locked(&game.step(idle(), ms(1000))));
+    assert!(landed);
+}
@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use glam::IVec2;
+use tetris_core::{Board, CurrentPiece, Input, Piece, Playfield, TopOut};
+
+mod common;
+
+use common::game_with;
+
+#[test]
+fn guideline_board_is_ten_by_twenty() {
+    let game = game_with(|rules| rules.playfield = Playfield::guideline());
+    assert_eq!(game.board().width(), 10);
+    assert_eq!(game.board().height(), 20);
+    assert_eq!(game.board().buffer_rows(), 20);
+}
+
+#[test]
+fn pieces_spawn_in_the_middle_columns() {
+    let playfield = Playfield::guideline();
+    let cells = CurrentPiece::new(Piece::I, &playfield).cells();
+    let mut columns: Vec<i32> = cells.iter().map(|cell| cell.x).collect();
+    columns.sort();
+    assert_eq!(columns, [3, 4, 5, 6]);
+    let t = CurrentPiece::new(Piece::T, &playfield).cells();
+    assert!(t.iter().all(|cell| (3..=5).contains(&cell.x)));
+    let o = CurrentPiece::new(Piece::O, &playfield).cells();
+    assert!(o.iter().all(|cell| (4..=5).contains(&cell.x)));
+    // On the same two rows as the T.
+    let rows = |cells: [IVec2; 4]| {
+        let mut rows: Vec<i32> = cells.iter().map(|cell| cell.y).collect();
+        rows.sort();
+        rows.dedup();
+        rows
+    };
+    assert_eq!(rows(o), rows(t));
+}
+
+#[test]
+fn spawn_point_and_rotation_are_configurable() {
+    let playfield = Playfield {
+        spawn_column: Some(0),
+        spawn_row: Some(5),
+        spawn_rotation: 1,
+        ..Playfield::guideline()
+    };
+    let game = game_with(|rules| rules.playfield = playfield);
+    assert_eq!(game.current_piece().position, IVec2::new(0, 5));
+    assert_eq!(game.current_piece().rotation, 1);
+}
+
+#[test]
+fn walls_follow_the_board_width() {
+    let mut game = game_with(|rules| rules.playfield = Playfield::guideline());
+    let right = Input {
+        right: true,
+        right_held: true,
+        ..Default::default()
+    };
+    game.step(right, Duration::ZERO);
+    for _ in 0..20 {
+        game.step(right, Duration::from_millis(1));
+    }
+    let rightmost = game.current_piece().cells().map(|cell| cell.x);
+    assert_eq!(rightmost.into_iter().max(), Some(9));
+}
+
+#[test]
+fn nothing_fits_above_the_buffer_rows() {
+    let board = Board::with_buffer(10, 20, 2);
+    assert!(!board.is_blocked(IVec2::new(0, 21)));
+    assert!(board.is_blocked(IVec2::new(0, 22)));
+}
+
+#[test]
+fn buffer_zone_keeps_what_locks_in_it() {
+    let mut board = Board::with_buffer(10, 20, 20);
+    assert_eq!(board.total_height(), 40);
+    // The O sits in the top right of its box, so this covers columns 0-1 of rows 20-21.
+    board.place_piece(Piece::O, IVec2::new(-1, 19), 0);
+    assert_eq!(board.get(0, 20), Some(Piece::O));
+    assert!(board.is_blocked(IVec2::new(1, 21)));
+    assert!(!board.is_blocked(IVec2::new(2, 21)));
+}
+
+#[test]
+fn clears_pull_the_buffer_zone_down() {
+    let mut board = Board::with_buffer(4, 2, 2);
+    for col in 0..4 {
+        board.set(col, 0, Some(Piece::I));
+    }
+    board.set(1, 2, Some(Piece::T));
+    assert_eq!(board.clear_full_rows(), 1);
+    assert_eq!(board.get(1, 1), Some(Piece::T));
+    assert_eq!(board.get(1, 2), None);
+}
+
+#[test]
+fn stacking_into_the_buffer_zone_does_not_crash() {
+    let mut game = game_with(|rules| {
+        rules.playfield.spawn_row = Some(20);
+        rules.top_out = TopOut::BlockOutOnly;
+    });
+    let (width, height) = (game.board().width(), game.board().height());
+    // Column 0 stays open so nothing clears.
+    for row in 0..height {
+        for col in 1..width {
+            game.board_mut().set(col, row, Some(Piece::O));
+        }
+    }
+    let hard_drop = Input {
+        hard_drop: true,
+        ..Default::default()
+    };
+    while !game.is_over() {
+        game.step(hard_drop, Duration::from_millis(16));
+    }
+    let board = game.board();
+    let above = (height..board.total_height())
+        .flat_map(|row| (0..width).map(move |col| (col, row)))
+        .filter(|&(col, row)| board.is_filled(col, row))
+        .count();
+    assert!(above > 0);
+}
@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use tetris_core::{Game, Input, RandomizerKind, Ruleset};
+
+#[test]
+fn next_pieces_spawn_in_order_across_bags() {
+    let hard_drop = Input {
+        hard_drop: true,
+        ..Default::default()
+    };
+    for randomizer in [RandomizerKind::SevenBag, RandomizerKind::Tgm3] {
+        let mut game = Game::seeded(
+            Ruleset {
+                randomizer,
+                ..Default::default()
+            },
+            3,
+        );
+        // Starting one piece into the first bag, seven pieces on reach into the second.
+        let upcoming = game.next_pieces(7);
+        assert_eq!(upcoming[0], game.next_piece());
+        assert_eq!(
+            game.next_pieces(7),
+            upcoming,
+            "peeking twice changes nothing"
+        );
+
+        let spawned: Vec<_> = (0..7)
+            .map(|_| {
+                game.step(hard_drop, Duration::ZERO);
+                game.current_piece().piece
+            })
+            .collect();
+        assert_eq!(spawned, upcoming);
+    }
+}
@@ -0,0 +1,139 @@
+use tetris_core::{Piece, RandomizerKind};
+
+const KINDS: [RandomizerKind; 7] = [
+    RandomizerKind::SevenBag,
+    RandomizerKind::FourteenBag,
+    RandomizerKind::Random,
+    RandomizerKind::Nes,
+    RandomizerKind::Tgm { rolls: 4 },
+    RandomizerKind::Tgm { rolls: 6 },
+    RandomizerKind::Tgm3,
+];
+
+const SEEDS: [u64; 3] = [1, 0xdead_beef, 20230401];
+
+fn deal(kind: RandomizerKind, seed: u64, count: usize) -> Vec<Piece> {
+    let mut randomizer = kind.build(seed);
+    (0..count).map(|_| randomizer.next_piece()).collect()
+}
+
+fn counts(pieces: &[Piece]) -> [usize; 7] {
+    let mut counts = [0; 7];
+    for &piece in pieces {
+        counts[piece as usize] += 1;
+    }
+    counts
+}
+
+/// How often a piece comes straight after itself.
+fn repeat_rate(pieces: &[Piece]) -> f64 {
+    let repeats = pieces.windows(2).filter(|pair| pair[0] == pair[1]).count();
+    repeats as f64 / (pieces.len() - 1) as f64
+}
+
+/// The longest run of pieces without a particular piece showing up.
+fn longest_drought(pieces: &[Piece]) -> usize {
+    Piece::ALL
+        .iter()
+        .map(|&piece| {
+            pieces
+                .split(|&other| other == piece)
+                .map(<[Piece]>::len)
+                .max()
+                .unwrap_or(0)
+        })
+        .max()
+        .unwrap()
+}
+
+#[test]
+fn same_seed_same_pieces() {
+    for kind in KINDS {
+        assert_eq!(deal(kind, 99, 200), deal(kind, 99, 200), "{kind:?}");
+        assert_ne!(deal(kind, 99, 200), deal(kind, 100, 200), "{kind:?}");
+    }
+}
+
+#[test]
+fn peek_shows_what_gets_dealt() {
+    for kind in KINDS {
+        let mut randomizer = kind.build(5);
+        for _ in 0..50 {
+            let ahead = randomizer.peek(7);
+            let dealt: Vec<Piece> = (0..7).map(|_| randomizer.next_piece()).collect();
+            assert_eq!(ahead, dealt, "{kind:?}");
+            // Peeking again must not have moved anything along.
+            assert_eq!(randomizer.peek(3), randomizer.peek(3), "{kind:?}");
+        }
+    }
+}
+
+#[test]
+fn every_piece_is_about_equally_likely() {
+    let count = 70_000;
+    for kind in KINDS {
+        for seed in SEEDS {
+            for piece_count in counts(&deal(kind, seed, count)) {
+                let share = piece_count as f64 / count as f64;
+                assert!((share - 1.0 / 7.0).abs() < 0.01, "{kind:?} {seed} {share}");
+            }
+        }
+    }
+}
+
+#[test]
+fn bags_hold_a_fixed_number_of_each() {
+    for seed in SEEDS {
+        for chunk in deal(RandomizerKind::SevenBag, seed, 7000).chunks(7) {
+            assert_eq!(counts(chunk), [1; 7]);
+        }
+        for chunk in deal(RandomizerKind::FourteenBag, seed, 7000).chunks(14) {
+            assert_eq!(counts(chunk), [2; 7]);
+        }
+        // Last piece of one bag to the first of the bag after next.
+        assert!(longest_drought(&deal(RandomizerKind::SevenBag, seed, 7000)) <= 12);
+        assert!(longest_drought(&deal(RandomizerKind::FourteenBag, seed, 7000)) <= 24);
+    }
+}
+
+#[test]
+fn repeat_rates() {
+    for seed in SEEDS {
+        let random = repeat_rate(&deal(RandomizerKind::Random, seed, 70_000));
+        assert!((random - 1.0 / 7.0).abs() < 0.01, "{random}");
+
+        // A quarter of the time it rolls again, and then repeats a seventh of the time.
+        let nes = repeat_rate(&deal(RandomizerKind::Nes, seed, 70_000));
+        assert!((nes - 1.0 / 28.0).abs() < 0.005, "{nes}");
+
+        let tgm = repeat_rate(&deal(RandomizerKind::Tgm { rolls: 4 }, seed, 70_000));
+        let tgm2 = repeat_rate(&deal(RandomizerKind::Tgm { rolls: 6 }, seed, 70_000));
+        let tgm3 = repeat_rate(&deal(RandomizerKind::Tgm3, seed, 70_000));
+        // All four rolls land in the history about (4/7)^4 of the time, and then a quarter of
+        // those are the last piece.
+        assert!((tgm - 0.027).abs() < 0.005, "{tgm}");
+        assert!(tgm2 < tgm, "{tgm2} {tgm}");
+        assert!(tgm3 < 0.01, "{tgm3}");
+    }
+}
+
+#[test]
+fn tgm_never_starts_with_s_z_or_o() {
+    for kind in [RandomizerKind::Tgm { rolls: 4 }, RandomizerKind::Tgm3] {
+        for seed in 0..500 {
+            let first = deal(kind, seed, 1)[0];
+            assert!(![Piece::S, Piece::Z, Piece::O].contains(&first), "{kind:?}");
+        }
+    }
+}
+
+#[test]
+fn tgm3_keeps_droughts_shorter_than_tgm() {
+    for seed in SEEDS {
+        let tgm = longest_drought(&deal(RandomizerKind::Tgm { rolls: 6 }, seed, 70_000));
+        let tgm3 = longest_drought(&deal(RandomizerKind::Tgm3, seed, 70_000));
+        let random = longest_drought(&deal(RandomizerKind::Random, seed, 70_000));
+        assert!(tgm3 < tgm, "{tgm3} {tgm}");
+        assert!(tgm < random, "{tgm} {random}");
+    }
+}
@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use tetris_core::{Game, GameEvent, Input, RandomizerKind, Ruleset};
+
+/// A bit of everything: moving, turning, holding, soft and hard drops, with lots of uneven
+/// steps so gravity, auto shift and lock delay all have something left over when it stops.
+fn script() -> Vec<(Input, Duration)> {
+    let mut script = vec![];
+    for turn in 0..40u64 {
+        let left = turn % 3 == 0;
+        script.push((
+            Input {
+                left,
+                left_held: left,
+                right: turn % 3 == 1,
+                right_held: turn % 3 == 1,
+                rotate_cw: turn % 2 == 0,
+                hold: turn % 7 == 3,
+                ..Default::default()
+            },
+            Duration::from_millis(17 * (turn % 5)),
+        ));
+        script.push((
+            Input {
+                left_held: left,
+                soft_drop: turn % 4 == 0,
+                ..Default::default()
+            },
+            Duration::from_millis(180 + turn),
+        ));
+        script.push((
+            Input {
+                hard_drop: turn % 2 == 1,
+                ..Default::default()
+            },
+            Duration::from_millis(33),
+        ));
+    }
+    script
+}
+
+fn play(game: &mut Game) -> Vec<GameEvent> {
+    script()
+        .into_iter()
+        .flat_map(|(input, dt)| game.step(input, dt))
+        .collect()
+}
+
+fn assert_same(a: &Game, b: &Game) {
+    assert_eq!(a.board(), b.board());
+    assert_eq!(a.current_piece(), b.current_piece());
+    assert_eq!(a.next_pieces(7), b.next_pieces(7));
+    assert_eq!(a.held_piece(), b.held_piece());
+    assert_eq!(a.can_hold(), b.can_hold());
+    assert_eq!(
+        (a.score(), a.level(), a.lines(), a.seed(), a.top_out()),
+        (b.score(), b.level(), b.lines(), b.seed(), b.top_out())
+    );
+}
+
+#[test]
+fn restarted_game_plays_like_a_fresh_one() {
+    for randomizer in [RandomizerKind::SevenBag, RandomizerKind::Tgm3] {
+        let rules = Ruleset {
+            randomizer,
+            ..Default::default()
+        };
+        let mut restarted = Game::seeded(rules.clone(), 1);
+        play(&mut restarted);
+        restarted.restart(2);
+
+        let mut fresh = Game::seeded(rules, 2);
+        assert_same(&restarted, &fresh);
+        assert_eq!(play(&mut restarted), play(&mut fresh));
+        assert_same(&restarted, &fresh);
+    }
+}
+
+#[test]
+fn restart_brings_back_a_finished_game() {
+    let mut game = Game::seeded(Ruleset::default(), 3);
+    let hard_drop = Input {
+        hard_drop: true,
+        ..Default::default()
+    };
+    while !game.is_over() {
+        game.step(hard_drop, Duration::ZERO);
+    }
+    game.restart(3);
+    assert_same(&game, &Game::seeded(Ruleset::default(), 3));
+}
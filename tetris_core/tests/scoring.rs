@@ -0,0 +1,188 @@
+use std::time::Duration;
+
+use glam::IVec2;
+use tetris_core::{
+    scoring, srs, Board, ClearAction, Game, GameEvent, Input, Piece, Rotation, Ruleset,
+    ScoringTable, TSpin,
+};
+
+mod common;
+
+use common::{board_from, piece_at};
+
+fn clear(lines: usize, t_spin: TSpin) -> ClearAction {
+    ClearAction {
+        lines,
+        t_spin,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn guideline_line_clears() {
+    let table = ScoringTable::Guideline;
+    assert_eq!(table.points(&clear(1, TSpin::None), 0), 100);
+    assert_eq!(table.points(&clear(2, TSpin::None), 0), 300);
+    assert_eq!(table.points(&clear(3, TSpin::None), 0), 500);
+    assert_eq!(table.points(&clear(4, TSpin::None), 0), 800);
+    // Level 0 is level 1 as far as the multiplier goes.
+    assert_eq!(table.points(&clear(4, TSpin::None), 4), 4000);
+}
+
+#[test]
+fn guideline_t_spins() {
+    let table = ScoringTable::Guideline;
+    assert_eq!(table.points(&clear(0, TSpin::Mini), 0), 100);
+    assert_eq!(table.points(&clear(1, TSpin::Mini), 0), 200);
+    assert_eq!(table.points(&clear(2, TSpin::Mini), 0), 400);
+    assert_eq!(table.points(&clear(0, TSpin::Full), 0), 400);
+    assert_eq!(table.points(&clear(1, TSpin::Full), 0), 800);
+    assert_eq!(table.points(&clear(2, TSpin::Full), 0), 1200);
+    assert_eq!(table.points(&clear(3, TSpin::Full), 0), 1600);
+}
+
+#[test]
+fn guideline_bonuses() {
+    let table = ScoringTable::Guideline;
+    let back_to_back = ClearAction {
+        back_to_back: true,
+        ..clear(2, TSpin::Full)
+    };
+    assert_eq!(table.points(&back_to_back, 0), 1800);
+
+    let combo = ClearAction {
+        combo: 3,
+        ..clear(1, TSpin::None)
+    };
+    assert_eq!(table.points(&combo, 1), (100 + 150) * 2);
+
+    let perfect = ClearAction {
+        perfect_clear: true,
+        ..clear(4, TSpin::None)
+    };
+    assert_eq!(table.points(&perfect, 0), 800 + 2000);
+    let perfect_back_to_back = ClearAction {
+        back_to_back: true,
+        ..perfect
+    };
+    assert_eq!(table.points(&perfect_back_to_back, 0), 1200 + 3200);
+}
+
+#[test]
+fn difficult_clears() {
+    assert!(clear(4, TSpin::None).is_difficult());
+    assert!(clear(1, TSpin::Mini).is_difficult());
+    assert!(!clear(3, TSpin::None).is_difficult());
+    assert!(!clear(0, TSpin::Full).is_difficult());
+}
+
+#[test]
+fn clear_names() {
+    assert_eq!(clear(0, TSpin::None).name(), None);
+    assert_eq!(clear(4, TSpin::None).name().unwrap(), "TETRIS");
+    assert_eq!(clear(2, TSpin::Full).name().unwrap(), "T-SPIN DOUBLE");
+    assert_eq!(clear(1, TSpin::Mini).name().unwrap(), "T-SPIN MINI SINGLE");
+    assert_eq!(clear(0, TSpin::Full).name().unwrap(), "T-SPIN");
+}
+
+#[test]
+fn nes_table_only_counts_lines() {
+    let table = ScoringTable::Nes;
+    assert_eq!(table.points(&clear(1, TSpin::None), 0), 40);
+    assert_eq!(table.points(&clear(4, TSpin::None), 2), 3600);
+    let everything = ClearAction {
+        perfect_clear: true,
+        back_to_back: true,
+        combo: 5,
+        ..clear(2, TSpin::Full)
+    };
+    assert_eq!(table.points(&everything, 0), 100);
+    assert_eq!(table.hard_drop_points(10), 0);
+}
+
+#[test]
+fn t_spin_double_is_a_full_t_spin() {
+    let board = board_from(&["..........", "..#..#....", "###...####", "####.#####"]);
+    let (t, kick) = srs::rotate(&board, &piece_at(Piece::T, 2, 1, 1), Rotation::Clockwise).unwrap();
+    assert_eq!(scoring::detect_t_spin(&board, &t, Some(kick)), TSpin::Full);
+    // Same spot, but it slid in rather than turning.
+    assert_eq!(scoring::detect_t_spin(&board, &t, None), TSpin::None);
+}
+
+#[test]
+fn t_spin_mini_and_the_last_kick_upgrade() {
+    // Flat on the floor with one of the corners it points at filled.
+    let board = board_from(&["#.........", ".........."]);
+    let t = piece_at(Piece::T, 0, -1, 0);
+    assert_eq!(scoring::detect_t_spin(&board, &t, Some(0)), TSpin::Mini);
+    assert_eq!(scoring::detect_t_spin(&board, &t, Some(4)), TSpin::Full);
+
+    let open = Board::new(10, 2);
+    assert_eq!(scoring::detect_t_spin(&open, &t, Some(0)), TSpin::None);
+}
+
+#[test]
+fn only_t_pieces_spin() {
+    let board = board_from(&["#.#.......", ".........."]);
+    let mut piece = piece_at(Piece::T, 0, -1, 0);
+    piece.piece = Piece::J;
+    assert_eq!(scoring::detect_t_spin(&board, &piece, Some(0)), TSpin::None);
+}
+
+/// A game that starts with an I piece, the seed is found rather than hard coded so changes to
+/// the randomizer don't break this.
+fn game_starting_with_i() -> Game {
+    (0..)
+        .map(|seed| Game::seeded(Ruleset::default(), seed))
+        .find(|game| game.current_piece().piece == Piece::I)
+        .unwrap()
+}
+
+#[test]
+fn drops_score_points() {
+    let mut game = game_starting_with_i();
+    let rows = game.current_piece().position.y - game.ghost_piece().position.y;
+    let hard_drop = Input {
+        hard_drop: true,
+        ..Default::default()
+    };
+    game.step(hard_drop, Duration::ZERO);
+    assert_eq!(game.score(), 2 * rows as usize);
+
+    let mut game = game_starting_with_i();
+    let soft_drop = Input {
+        soft_drop: true,
+        ..Default::default()
+    };
+    // Soft drop gravity is 50ms a row.
+    game.step(soft_drop, Duration::from_millis(160));
+    assert_eq!(game.score(), 3);
+}
+
+#[test]
+fn single_perfect_clear() {
+    let mut game = game_starting_with_i();
+    let landing = game.ghost_piece().cells();
+    let width = game.board().width();
+    for col in 0..width {
+        if !landing.contains(&IVec2::new(col as i32, 0)) {
+            game.board_mut().set(col, 0, Some(Piece::O));
+        }
+    }
+    let rows = game.current_piece().position.y - game.ghost_piece().position.y;
+    let hard_drop = Input {
+        hard_drop: true,
+        ..Default::default()
+    };
+    let events = game.step(hard_drop, Duration::ZERO);
+    let action = ClearAction {
+        perfect_clear: true,
+        ..clear(1, TSpin::None)
+    };
+    assert!(events.contains(&GameEvent::Cleared {
+        action,
+        points: 100 + 800
+    }));
+    assert_eq!(game.score(), 900 + 2 * rows as usize);
+    assert!(game.board().is_empty());
+}
@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+use tetris_core::{Bag, Game, Input, Piece, Randomizer, Ruleset};
+
+fn deal(bag: &mut Bag, count: usize) -> Vec<Piece> {
+    (0..count).map(|_| bag.next_piece()).collect()
+}
+
+#[test]
+fn same_seed_same_pieces() {
+    assert_eq!(deal(&mut Bag::new(42), 70), deal(&mut Bag::new(42), 70));
+    assert_ne!(deal(&mut Bag::new(42), 70), deal(&mut Bag::new(43), 70));
+}
+
+#[test]
+fn every_bag_has_one_of_each() {
+    let mut bag = Bag::new(7);
+    for _ in 0..100 {
+        let mut pieces = deal(&mut bag, 7);
+        pieces.sort_by_key(|piece| *piece as usize);
+        assert_eq!(pieces, Piece::ALL);
+    }
+}
+
+#[test]
+fn shuffle_is_uniform() {
+    // Each piece should be first in the bag about a seventh of the time.
+    let mut firsts = [0; 7];
+    let mut bag = Bag::new(1);
+    let bags = 70_000;
+    for _ in 0..bags {
+        firsts[bag.next_piece() as usize] += 1;
+        deal(&mut bag, 6);
+    }
+    for count in firsts {
+        assert!((9_500..10_500).contains(&count), "{firsts:?}");
+    }
+}
+
+#[test]
+fn seeded_games_play_the_same_pieces() {
+    let hard_drop = Input {
+        hard_drop: true,
+        ..Default::default()
+    };
+    let play = |seed| {
+        let mut game = Game::seeded(Ruleset::default(), seed);
+        assert_eq!(game.seed(), seed);
+        (0..20)
+            .map(|_| {
+                let piece = game.current_piece().piece;
+                game.step(hard_drop, Duration::ZERO);
+                piece
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(play(1234), play(1234));
+}
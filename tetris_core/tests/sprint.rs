@@ -0,0 +1,93 @@
+use tetris_core::{GameEvent, Goal, Input, Pace, Records, SprintRun};
+
+mod common;
+
+use common::{default_game, game_with, hard_drop, ms, set_up_single};
+
+fn run(goal: usize, splits: &[u64]) -> SprintRun {
+    let mut run = SprintRun::new(goal);
+    for (line, &at) in splits.iter().enumerate() {
+        run.record(line + 1, ms(at));
+    }
+    run
+}
+
+#[test]
+fn reaching_the_line_goal_ends_the_game() {
+    let mut game = game_with(|rules| rules.goal = Goal::Lines(1));
+    game.step(Input::default(), ms(500));
+    set_up_single(&mut game);
+    let events = game.step(hard_drop(), ms(20));
+    assert!(events.contains(&GameEvent::GoalReached));
+    assert!(game.goal_reached());
+    assert!(game.is_over());
+    assert_eq!(game.top_out(), None);
+
+    assert_eq!(game.elapsed(), ms(520));
+    let sprint = game.sprint().unwrap();
+    assert_eq!(sprint.splits(), &[ms(520)]);
+    assert_eq!(sprint.time(), Some(ms(520)));
+    game.step(Input::default(), ms(100));
+    assert_eq!(game.elapsed(), ms(520));
+}
+
+#[test]
+fn endless_games_carry_on_past_any_line_count() {
+    let mut game = default_game();
+    set_up_single(&mut game);
+    game.step(hard_drop(), ms(20));
+    assert_eq!(game.lines(), 1);
+    assert!(!game.is_over());
+    assert_eq!(game.sprint(), None);
+}
+
+#[test]
+fn lines_cleared_together_share_a_split() {
+    let mut sprint = SprintRun::new(4);
+    sprint.record(1, ms(1000));
+    sprint.record(3, ms(2500));
+    assert_eq!(sprint.splits(), &[ms(1000), ms(2500), ms(2500)]);
+    assert_eq!(sprint.time(), None);
+    // Clearing past the goal still finishes it on the goal.
+    sprint.record(6, ms(4000));
+    assert_eq!(sprint.time(), Some(ms(4000)));
+    assert_eq!(sprint.splits().len(), 4);
+}
+
+#[test]
+fn pace_is_against_the_same_line_of_the_best_run() {
+    let best = run(3, &[1000, 2000, 3000]);
+    assert_eq!(run(3, &[]).pace(&best), None);
+    assert_eq!(run(3, &[900]).pace(&best), Some(Pace::Ahead(ms(100))));
+    assert_eq!(
+        run(3, &[900, 2300]).pace(&best),
+        Some(Pace::Behind(ms(300)))
+    );
+}
+
+#[test]
+fn only_faster_finished_runs_are_kept() {
+    let mut records = Records::default();
+    assert!(!records.submit_sprint(&run(2, &[500])));
+    assert!(records.submit_sprint(&run(2, &[500, 1500])));
+    assert!(!records.submit_sprint(&run(2, &[400, 1600])));
+    assert!(records.submit_sprint(&run(2, &[700, 1400])));
+    // Each goal has its own best.
+    assert!(records.submit_sprint(&run(1, &[2000])));
+    assert_eq!(records.best_sprint(2).unwrap().time(), Some(ms(1400)));
+    assert_eq!(records.best_sprint(40), None);
+}
+
+#[test]
+fn records_save_and_load_as_text() {
+    let mut records = Records::default();
+    records.submit_sprint(&run(2, &[700, 1400]));
+    records.submit_sprint(&run(3, &[100, 200, 300]));
+    let text = records.to_string();
+    assert_eq!(text, "sprint 2 700 1400\nsprint 3 100 200 300\n");
+    assert_eq!(text.parse::<Records>(), Ok(records));
+
+    assert!("".parse::<Records>().unwrap().best_sprint(40).is_none());
+    assert!("sprint 3 100 200".parse::<Records>().is_err());
+    assert!("marathon 3".parse::<Records>().is_err());
+}
@@ -0,0 +1,218 @@
+use std::time::Duration;
+
+use glam::IVec2;
+use tetris_core::{srs, Board, Game, HalfTurnKicks, Input, Piece, Rotation, Ruleset};
+
+mod common;
+
+use common::{board_from, piece_at};
+
+#[test]
+fn rotates_in_place_when_nothing_is_in_the_way() {
+    let board = Board::new(10, 20);
+    let t = piece_at(Piece::T, 3, 10, 0);
+
+    let (rotated, kick) = srs::rotate(&board, &t, Rotation::Clockwise).unwrap();
+    assert_eq!(
+        (rotated.position, rotated.rotation, kick),
+        (t.position, 1, 0)
+    );
+
+    let (rotated, kick) = srs::rotate(&board, &t, Rotation::CounterClockwise).unwrap();
+    assert_eq!(
+        (rotated.position, rotated.rotation, kick),
+        (t.position, 3, 0)
+    );
+}
+
+#[test]
+fn four_turns_get_back_to_spawn() {
+    let board = Board::new(10, 20);
+    for piece in Piece::ALL {
+        let start = piece_at(piece, 3, 10, 0);
+        let mut current = start;
+        for _ in 0..4 {
+            current = srs::rotate(&board, &current, Rotation::Clockwise)
+                .unwrap()
+                .0;
+        }
+        assert_eq!(current.cells(), start.cells(), "{piece:?}");
+    }
+}
+
+#[test]
+fn o_piece_never_moves() {
+    let board = Board::new(10, 20);
+    let o = piece_at(Piece::O, 3, 10, 0);
+    let (rotated, _) = srs::rotate(&board, &o, Rotation::Clockwise).unwrap();
+    assert_eq!(rotated.cells(), o.cells());
+}
+
+#[test]
+fn i_piece_kicks_off_the_left_wall() {
+    let board = Board::new(10, 20);
+    // Vertical in column 0, flat would poke two cells out of the wall.
+    let i = piece_at(Piece::I, -2, 5, 1);
+
+    let (rotated, kick) = srs::rotate(&board, &i, Rotation::Clockwise).unwrap();
+    assert_eq!(kick, 2);
+    assert_eq!(rotated.rotation, 2);
+    assert_eq!(
+        rotated.cells(),
+        [
+            IVec2::new(0, 6),
+            IVec2::new(1, 6),
+            IVec2::new(2, 6),
+            IVec2::new(3, 6)
+        ]
+    );
+}
+
+#[test]
+fn t_piece_kicks_off_the_right_wall() {
+    let board = Board::new(10, 20);
+    // Pointing left against the wall, the spawn state needs a column that isn't there.
+    let t = piece_at(Piece::T, 8, 5, 3);
+
+    let (rotated, kick) = srs::rotate(&board, &t, Rotation::Clockwise).unwrap();
+    assert_eq!(
+        (rotated.position, rotated.rotation, kick),
+        (IVec2::new(7, 5), 0, 1)
+    );
+}
+
+#[test]
+fn i_piece_kicks_up_off_the_floor() {
+    let board = Board::new(10, 20);
+    // Lying flat on the floor, standing up needs two rows that are below the board.
+    let i = piece_at(Piece::I, 3, -2, 0);
+    assert!(!board.check_piece_obstructed(i.piece, i.position, i.rotation));
+
+    let (rotated, kick) = srs::rotate(&board, &i, Rotation::Clockwise).unwrap();
+    assert_eq!(kick, 4);
+    assert_eq!((rotated.position, rotated.rotation), (IVec2::new(4, 0), 1));
+}
+
+#[test]
+fn t_spin_double_kicks_down_into_the_slot() {
+    let board = board_from(&["..........", "..#..#....", "###...####", "####.#####"]);
+    // Pointing right beside the slot, turning to point down is blocked in place and one across,
+    // the third kick drops it in.
+    let t = piece_at(Piece::T, 2, 1, 1);
+    assert!(!board.check_piece_obstructed(t.piece, t.position, t.rotation));
+
+    let (rotated, kick) = srs::rotate(&board, &t, Rotation::Clockwise).unwrap();
+    assert_eq!(kick, 2);
+    assert_eq!((rotated.position, rotated.rotation), (IVec2::new(3, 0), 2));
+
+    let mut board = board;
+    board.place_piece(rotated.piece, rotated.position, rotated.rotation);
+    assert_eq!(board.clear_full_rows(), 2);
+}
+
+#[test]
+fn t_spin_triple_uses_the_last_kick() {
+    let board = board_from(&[
+        "....##....",
+        ".....#....",
+        "####.#####",
+        "###..#####",
+        "####.#####",
+    ]);
+    // Spawn state tucked under the roof, the only way in is two rows down and one across.
+    let t = piece_at(Piece::T, 2, 2, 0);
+    assert!(!board.check_piece_obstructed(t.piece, t.position, t.rotation));
+
+    let (rotated, kick) = srs::rotate(&board, &t, Rotation::CounterClockwise).unwrap();
+    assert_eq!(kick, 4);
+    assert_eq!((rotated.position, rotated.rotation), (IVec2::new(3, 0), 3));
+
+    let mut board = board;
+    board.place_piece(rotated.piece, rotated.position, rotated.rotation);
+    assert_eq!(board.clear_full_rows(), 3);
+}
+
+#[test]
+fn t_spin_triple_mirrored() {
+    let board = board_from(&[
+        "....##....",
+        "....#.....",
+        "#####.####",
+        "#####..###",
+        "#####.####",
+    ]);
+    let t = piece_at(Piece::T, 5, 2, 0);
+    assert!(!board.check_piece_obstructed(t.piece, t.position, t.rotation));
+
+    let (rotated, kick) = srs::rotate(&board, &t, Rotation::Clockwise).unwrap();
+    assert_eq!(kick, 4);
+    assert_eq!((rotated.position, rotated.rotation), (IVec2::new(4, 0), 1));
+
+    let mut board = board;
+    board.place_piece(rotated.piece, rotated.position, rotated.rotation);
+    assert_eq!(board.clear_full_rows(), 3);
+}
+
+#[test]
+fn fails_without_moving_when_every_kick_is_blocked() {
+    let board = board_from(&[
+        "##########",
+        "###...####",
+        "####.#####",
+        "##########",
+        "##########",
+        "##########",
+    ]);
+    let t = piece_at(Piece::T, 3, 3, 2);
+    assert!(!board.check_piece_obstructed(t.piece, t.position, t.rotation));
+
+    assert!(srs::rotate(&board, &t, Rotation::Clockwise).is_none());
+    assert!(srs::rotate(&board, &t, Rotation::CounterClockwise).is_none());
+}
+
+#[test]
+fn half_turn_goes_straight_to_the_opposite_state() {
+    let board = Board::new(10, 20);
+    let t = piece_at(Piece::T, 3, 10, 1);
+    let (rotated, kick) = srs::rotate(&board, &t, Rotation::Half).unwrap();
+    assert_eq!(
+        (rotated.position, rotated.rotation, kick),
+        (t.position, 3, 0)
+    );
+}
+
+#[test]
+fn half_turn_kicks_up_off_the_floor() {
+    let board = Board::new(10, 20);
+    // Flat side down on the floor, pointing down would go through it.
+    let t = piece_at(Piece::T, 3, -1, 0);
+    assert!(!board.check_piece_obstructed(t.piece, t.position, t.rotation));
+
+    let (rotated, kick) = srs::rotate(&board, &t, Rotation::Half).unwrap();
+    assert_eq!(kick, 1);
+    assert_eq!((rotated.position, rotated.rotation), (IVec2::new(3, 0), 2));
+
+    let in_place = srs::rotate_with(&board, &t, Rotation::Half, HalfTurnKicks::InPlace);
+    assert!(in_place.is_none());
+}
+
+#[test]
+fn half_turns_only_when_the_ruleset_has_them() {
+    let turn = Input {
+        rotate_180: true,
+        ..Default::default()
+    };
+    let mut game = Game::seeded(Ruleset::default(), 4);
+    game.step(turn, Duration::ZERO);
+    assert_eq!(game.current_piece().rotation, 2);
+
+    let mut game = Game::seeded(
+        Ruleset {
+            half_turn: None,
+            ..Default::default()
+        },
+        4,
+    );
+    game.step(turn, Duration::ZERO);
+    assert_eq!(game.current_piece().rotation, 0);
+}
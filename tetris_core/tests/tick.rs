@@ -0,0 +1,87 @@
+use tetris_core::{Game, GameEvent, GravityTable, Input, Ruleset, Timing};
+
+/// A made up but repeatable run of key presses, one per tick.
+fn script(ticks: usize) -> Vec<Input> {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    (0..ticks)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let bits = state;
+            Input {
+                rotate_cw: bits & 0b1111 == 1,
+                rotate_ccw: bits & 0b1111 == 2,
+                rotate_180: bits & 0b1111 == 3,
+                left: bits >> 4 & 0b111 == 1,
+                left_held: bits >> 7 & 1 == 1,
+                right: bits >> 8 & 0b111 == 1,
+                right_held: bits >> 11 & 1 == 1,
+                soft_drop: bits >> 12 & 0b11 == 1,
+                hard_drop: bits >> 14 & 0b1_1111 == 1,
+                hold: bits >> 19 & 0b11_1111 == 1,
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+fn play(rules: Ruleset, inputs: &[Input]) -> (Game, Vec<GameEvent>) {
+    let mut game = Game::seeded(rules, 2024);
+    let events = inputs.iter().flat_map(|&input| game.tick(input)).collect();
+    (game, events)
+}
+
+#[test]
+fn same_ticks_same_game() {
+    let rules = Ruleset {
+        timing: Timing::tgm(),
+        ..Default::default()
+    };
+    let inputs = script(5_000);
+    let (first, first_events) = play(rules.clone(), &inputs);
+    let (second, second_events) = play(rules, &inputs);
+
+    assert!(first_events
+        .iter()
+        .any(|event| matches!(event, GameEvent::PieceLocked(_))));
+    assert_eq!(first_events, second_events);
+    assert_eq!(first.board(), second.board());
+    assert_eq!(first.current_piece(), second.current_piece());
+    assert_eq!(
+        (first.score(), first.lines(), first.level()),
+        (second.score(), second.lines(), second.level())
+    );
+}
+
+#[test]
+fn a_sixtieth_of_a_g_is_a_row_every_sixty_ticks() {
+    let mut game = Game::seeded(
+        Ruleset {
+            gravity: "0 0.0166666666666666667".parse::<GravityTable>().unwrap(),
+            ..Default::default()
+        },
+        1,
+    );
+    let start = game.current_piece().position.y;
+    for _ in 0..59 {
+        game.tick(Input::default());
+    }
+    assert_eq!(game.current_piece().position.y, start);
+    game.tick(Input::default());
+    assert_eq!(game.current_piece().position.y, start - 1);
+}
+
+#[test]
+fn latched_presses_are_not_lost_between_ticks() {
+    let pressed = Input {
+        left: true,
+        left_held: true,
+        ..Default::default()
+    };
+    let released = Input::default();
+    let latched = pressed.latch(released);
+    assert!(latched.left);
+    assert!(!latched.left_held);
+    assert!(!latched.held().left);
+}
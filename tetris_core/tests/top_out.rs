@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use glam::IVec2;
+use tetris_core::{Game, GameEvent, Input, Piece, TopOut, TopOutReason};
+
+mod common;
+
+use common::{default_game, game_with, hard_drop};
+
+#[test]
+fn stacking_to_the_top_blocks_out() {
+    let mut game = default_game();
+    let (width, height) = (game.board().width(), game.board().height());
+    // Leave column 0 open so nothing ever clears.
+    for row in 0..height - 4 {
+        for col in 1..width {
+            game.board_mut().set(col, row, Some(Piece::O));
+        }
+    }
+
+    let mut events = vec![];
+    for _ in 0..10 {
+        events = game.step(hard_drop(), Duration::from_secs(1));
+        if game.is_over() {
+            break;
+        }
+    }
+    assert_eq!(game.top_out(), Some(TopOutReason::BlockOut));
+    assert_eq!(
+        events.last(),
+        Some(&GameEvent::GameOver(TopOutReason::BlockOut))
+    );
+}
+
+#[test]
+fn nothing_moves_once_the_game_is_over() {
+    let mut game = default_game();
+    let (width, height) = (game.board().width(), game.board().height());
+    for row in 0..height - 4 {
+        for col in 1..width {
+            game.board_mut().set(col, row, Some(Piece::O));
+        }
+    }
+    while !game.is_over() {
+        game.step(hard_drop(), Duration::from_secs(1));
+    }
+
+    let piece = *game.current_piece();
+    assert!(game.step(hard_drop(), Duration::from_secs(1)).is_empty());
+    assert_eq!(game.current_piece().position, piece.position);
+
+    game.restart(7);
+    assert!(!game.is_over());
+    assert_eq!((game.score(), game.level(), game.lines()), (0, 0, 0));
+}
+
+#[test]
+fn lock_out_variants() {
+    let height = 20;
+    let inside = [IVec2::new(0, 19); 4];
+    let straddling = [
+        IVec2::new(0, 19),
+        IVec2::new(1, 19),
+        IVec2::new(1, 20),
+        IVec2::new(2, 20),
+    ];
+    let above = [IVec2::new(0, 20); 4];
+
+    assert!(!TopOut::Guideline.is_lock_out(&inside, height));
+    assert!(!TopOut::Guideline.is_lock_out(&straddling, height));
+    assert!(TopOut::Guideline.is_lock_out(&above, height));
+
+    assert!(!TopOut::Partial.is_lock_out(&inside, height));
+    assert!(TopOut::Partial.is_lock_out(&straddling, height));
+    assert!(TopOut::Partial.is_lock_out(&above, height));
+
+    assert!(!TopOut::BlockOutOnly.is_lock_out(&above, height));
+}
+
+/// Stacks `rows` rows with the right hand column left open so nothing clears, then drops a T
+/// against the left wall onto them. Pieces spawn above the playfield so there's room for it.
+fn drop_t_on_a_stack(top_out: TopOut, rows: usize) -> (Game, Vec<GameEvent>) {
+    let mut game = game_with(|rules| {
+        rules.top_out = top_out;
+        rules.playfield.spawn_row = Some(rules.playfield.height as i32 - 1);
+    });
+    let width = game.board().width();
+    for row in 0..rows {
+        for col in 0..width - 1 {
+            game.board_mut().set(col, row, Some(Piece::O));
+        }
+    }
+    game.debug_set_piece(Piece::T);
+    let left = Input {
+        left: true,
+        ..Default::default()
+    };
+    for _ in 0..width {
+        game.step(left, Duration::ZERO);
+    }
+    let events = game.step(hard_drop(), Duration::ZERO);
+    (game, events)
+}
+
+#[test]
+fn locking_wholly_above_the_playfield_locks_out() {
+    let height = default_game().board().height();
+    for top_out in [TopOut::Guideline, TopOut::Partial] {
+        let (game, events) = drop_t_on_a_stack(top_out, height);
+        assert_eq!(game.top_out(), Some(TopOutReason::LockOut), "{top_out:?}");
+        assert_eq!(
+            events.last(),
+            Some(&GameEvent::GameOver(TopOutReason::LockOut))
+        );
+    }
+}
+
+#[test]
+fn locking_partly_above_the_playfield_only_locks_out_under_partial() {
+    let height = default_game().board().height();
+    let (game, _) = drop_t_on_a_stack(TopOut::Guideline, height - 1);
+    assert!(!game.is_over());
+
+    let (game, events) = drop_t_on_a_stack(TopOut::Partial, height - 1);
+    assert_eq!(game.top_out(), Some(TopOutReason::LockOut));
+    assert_eq!(
+        events.last(),
+        Some(&GameEvent::GameOver(TopOutReason::LockOut))
+    );
+}
+
+#[test]
+fn block_out_only_keeps_going_above_the_playfield() {
+    let height = default_game().board().height();
+    let (game, events) = drop_t_on_a_stack(TopOut::BlockOutOnly, height);
+    assert!(!game.is_over());
+    assert!(events.contains(&GameEvent::PieceLocked(Piece::T)));
+}
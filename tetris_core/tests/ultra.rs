@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use tetris_core::{GameEvent, Goal, Input, Records};
+
+mod common;
+
+use common::game_with;
+
+#[test]
+fn play_stops_when_time_runs_out() {
+    let limit = Duration::from_secs(2);
+    let mut game = game_with(|rules| rules.goal = Goal::Time(limit));
+    assert!(game
+        .step(Input::default(), Duration::from_millis(1999))
+        .is_empty());
+    assert!(!game.is_over());
+
+    let drop = Input {
+        hard_drop: true,
+        ..Default::default()
+    };
+    let events = game.step(drop, Duration::from_millis(20));
+    assert_eq!(events, vec![GameEvent::GoalReached]);
+    assert!(game.goal_reached());
+    assert_eq!(game.top_out(), None);
+    assert_eq!(game.elapsed(), limit);
+    assert_eq!(game.score(), 0);
+}
+
+#[test]
+fn each_time_limit_keeps_its_own_best_score() {
+    let two_minutes = Duration::from_secs(120);
+    let mut records = Records::default();
+    assert_eq!(records.best_ultra(two_minutes), None);
+    assert!(records.submit_ultra(two_minutes, 5000));
+    assert!(!records.submit_ultra(two_minutes, 5000));
+    assert!(!records.submit_ultra(two_minutes, 4000));
+    assert!(records.submit_ultra(two_minutes, 6000));
+    assert!(records.submit_ultra(Duration::from_secs(60), 100));
+    assert_eq!(records.best_ultra(two_minutes), Some(6000));
+
+    let text = records.to_string();
+    assert_eq!(text, "ultra 120000 6000\nultra 60000 100\n");
+    assert_eq!(text.parse::<Records>(), Ok(records));
+    assert!("ultra 120000".parse::<Records>().is_err());
+}